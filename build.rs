@@ -0,0 +1,50 @@
+// Generates one test per example input in examples/dayNN/NAME.txt, which
+// tests/examples.rs includes. Each example needs an answers file next to it,
+// examples/dayNN/NAME.expected, in the same format as answers/dayNN.toml.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const EXAMPLES_DIR: &str = "examples";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", EXAMPLES_DIR);
+
+    let mut examples = Vec::new();
+    if let Ok(days) = fs::read_dir(EXAMPLES_DIR) {
+        for day_dir in days.map(|entry| entry.unwrap().path()) {
+            let day = match day_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("day"))
+                .and_then(|number| number.parse::<u8>().ok()) {
+                Some(day) if day_dir.is_dir() => day,
+                _ => continue,
+            };
+
+            println!("cargo:rerun-if-changed={}", day_dir.display());
+            for path in fs::read_dir(&day_dir).unwrap().map(|entry| entry.unwrap().path()) {
+                if path.extension().is_some_and(|ext| ext == "txt") {
+                    let name = path.file_stem().unwrap().to_str().unwrap().to_owned();
+                    examples.push((day, name));
+                }
+            }
+        }
+    }
+    examples.sort();
+
+    let mut tests = String::new();
+    for (day, name) in examples {
+        let ident = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect::<String>();
+        writeln!(tests, "#[test]\nfn day{:02}_{}() {{\n    check_example({}, {:?});\n}}\n",
+            day, ident, day, name).unwrap();
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("example_tests.rs");
+    fs::write(out, tests).unwrap();
+}
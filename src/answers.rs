@@ -0,0 +1,164 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::error::Error;
+
+use crate::solution::Part;
+
+pub const ANSWERS_DIR: &str = "answers";
+
+/// Known answers to a day's puzzle, as stored in `answers/dayNN.toml`:
+///
+/// ```toml
+/// part1 = 17
+/// part2 = """
+/// #####
+/// #####
+/// """
+/// ```
+///
+/// Integers and strings are both accepted; all answers are compared as strings.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(PathBuf, io::Error),
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnswersError::Io(path, e) => write!(f, "Cannot access {}: {}", path.display(), e),
+            AnswersError::Invalid(path, e) => write!(f, "Invalid answers in {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for AnswersError {}
+
+pub fn answers_path(day: u8) -> PathBuf {
+    Path::new(ANSWERS_DIR).join(format!("day{:02}.toml", day))
+}
+
+fn key(part: Part) -> &'static str {
+    match part {
+        Part::One => "part1",
+        Part::Two => "part2",
+    }
+}
+
+impl Answers {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: String) {
+        match part {
+            Part::One => self.part1 = Some(answer),
+            Part::Two => self.part2 = Some(answer),
+        }
+    }
+
+    pub fn from_toml(s: &str) -> Result<Answers, String> {
+        let table = s.parse::<toml::Table>().map_err(|e| e.to_string())?;
+
+        if let Some(unknown) = table.keys().find(|k| !Part::ALL.iter().any(|p| key(*p) == *k)) {
+            return Err(format!("unknown key '{}'", unknown));
+        }
+
+        let mut answers = Answers::default();
+        for part in Part::ALL {
+            match table.get(key(part)) {
+                Some(toml::Value::String(s)) => answers.set(part, s.clone()),
+                Some(toml::Value::Integer(i)) => answers.set(part, i.to_string()),
+                Some(_) => return Err(format!("'{}' has to be a string or an integer", key(part))),
+                None => (),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut table = toml::Table::new();
+        for part in Part::ALL {
+            if let Some(answer) = self.get(part) {
+                // Keep numbers readable as numbers when editing the file by hand
+                let value = match answer.parse::<i64>() {
+                    Ok(i) if i.to_string() == answer => toml::Value::Integer(i),
+                    _ => toml::Value::String(answer.to_owned()),
+                };
+                table.insert(key(part).to_owned(), value);
+            }
+        }
+
+        table.to_string()
+    }
+
+    /// Loads the answers stored for `day`, if there are any
+    pub fn load(day: u8) -> Result<Option<Answers>, AnswersError> {
+        let path = answers_path(day);
+        match fs::read_to_string(&path) {
+            Ok(s) => Answers::from_toml(&s)
+                .map(Some)
+                .map_err(|e| AnswersError::Invalid(path, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AnswersError::Io(path, e)),
+        }
+    }
+
+    pub fn save(&self, day: u8) -> Result<(), AnswersError> {
+        let path = answers_path(day);
+        fs::create_dir_all(ANSWERS_DIR)
+            .and_then(|_| fs::write(&path, self.to_toml()))
+            .map_err(|e| AnswersError::Io(path, e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_from_toml() {
+        let answers = Answers::from_toml("part1 = 17\npart2 = \"\"\"\n#####\n#...#\n#####\n\"\"\"\n").unwrap();
+
+        assert_eq!(answers.get(Part::One), Some("17"));
+        assert_eq!(answers.get(Part::Two), Some("#####\n#...#\n#####\n"));
+    }
+
+    #[test]
+    pub fn test_from_toml_partial() {
+        let answers = Answers::from_toml("part2 = \"abc\"").unwrap();
+
+        assert_eq!(answers, Answers { part1: None, part2: Some(String::from("abc")) });
+    }
+
+    #[test]
+    pub fn test_from_toml_invalid() {
+        assert!(Answers::from_toml("part3 = 1").is_err());
+        assert!(Answers::from_toml("part1 = 1.5").is_err());
+    }
+
+    #[test]
+    pub fn test_toml_roundtrip() {
+        let answers = Answers {
+            part1: Some(String::from("2188189693529")),
+            part2: Some(String::from("#..#\n.##.\n")),
+        };
+
+        let toml = answers.to_toml();
+
+        assert!(toml.starts_with("part1 = 2188189693529\n"));
+        assert_eq!(Answers::from_toml(&toml).unwrap(), answers);
+    }
+}
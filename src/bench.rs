@@ -0,0 +1,238 @@
+use std::error::Error;
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Part, Puzzle};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { warmup: 3, iterations: 25 }
+    }
+}
+
+/// Summary of repeated timings, all in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p90_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartReport {
+    pub part: u8,
+    pub solver: Stats,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayReport {
+    pub day: u8,
+    pub generator: Stats,
+    pub parts: Vec<PartReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    pub warmup: usize,
+    pub iterations: usize,
+    pub days: Vec<DayReport>,
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+    Parse(ParseError),
+    Solve(SolveError),
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BenchError::Parse(e) => write!(f, "{}", e),
+            BenchError::Solve(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for BenchError {}
+
+// Nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "Cannot compute stats without samples");
+
+        let mut sorted = samples
+            .iter()
+            .map(|d| d.as_nanos() as u64)
+            .collect::<Vec<u64>>();
+        sorted.sort_unstable();
+
+        let mid = sorted.len() / 2;
+        let median_ns = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        Stats {
+            min_ns: sorted[0],
+            median_ns,
+            p90_ns: percentile(&sorted, 90),
+            p99_ns: percentile(&sorted, 99),
+            max_ns: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let d = Duration::from_nanos;
+        write!(f, "median {:?} (p90 {:?}, p99 {:?}, min {:?}, max {:?})",
+            d(self.median_ns), d(self.p90_ns), d(self.p99_ns), d(self.min_ns), d(self.max_ns))
+    }
+}
+
+fn sample<F: FnMut()>(config: &BenchConfig, mut f: F) -> Stats {
+    for _ in 0..config.warmup {
+        f();
+    }
+
+    let samples = (0..config.iterations.max(1))
+        .map(|_| {
+            let start_time = Instant::now();
+            f();
+            start_time.elapsed()
+        })
+        .collect::<Vec<Duration>>();
+
+    Stats::from_samples(&samples)
+}
+
+/// Times the generator and the given parts of a puzzle separately. Every part
+/// is run against a single parsed input, so solver timings exclude parsing.
+/// Parts are solved once up front, so that failing ones are not timed.
+pub fn bench_puzzle(puzzle: &Puzzle, input: &str, parts: &[Part], config: &BenchConfig)
+    -> Result<DayReport, BenchError> {
+    let parsed = puzzle.parse(input).map_err(BenchError::Parse)?;
+    for part in parts {
+        puzzle.solve(&parsed, *part).map_err(BenchError::Solve)?;
+    }
+
+    let generator = sample(config, || {
+        black_box(puzzle.parse(black_box(input)).ok());
+    });
+
+    let parts = parts
+        .iter()
+        .map(|part| PartReport {
+            part: part.number(),
+            solver: sample(config, || {
+                black_box(puzzle.solve(&parsed, *part).ok());
+            }),
+        })
+        .collect();
+
+    Ok(DayReport { day: puzzle.day, generator, parts })
+}
+
+/// Relative change of a median compared to a baseline, e.g. -0.25 for 25% faster
+pub fn median_change(baseline: &Stats, current: &Stats) -> f64 {
+    (current.median_ns as f64 - baseline.median_ns as f64) / baseline.median_ns.max(1) as f64
+}
+
+impl Report {
+    pub fn day(&self, day: u8) -> Option<&DayReport> {
+        self.days.iter().find(|d| d.day == day)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Report is always serializable")
+    }
+
+    pub fn from_json(s: &str) -> Result<Report, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+}
+
+impl DayReport {
+    pub fn part(&self, part: u8) -> Option<&PartReport> {
+        self.parts.iter().find(|p| p.part == part)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution;
+
+    #[test]
+    pub fn test_stats_from_samples() {
+        let samples = (1..=10).rev().map(Duration::from_nanos).collect::<Vec<Duration>>();
+
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats, Stats { min_ns: 1, median_ns: 5, p90_ns: 9, p99_ns: 10, max_ns: 10 });
+    }
+
+    #[test]
+    pub fn test_bench_puzzle() {
+        let puzzle = solution::find(6).unwrap();
+        let config = BenchConfig { warmup: 1, iterations: 3 };
+
+        let report = bench_puzzle(puzzle, "3,4,3,1,2", &[Part::Two], &config).unwrap();
+
+        assert_eq!(report.day, 6);
+        assert_eq!(report.parts.len(), 1);
+        assert_eq!(report.parts[0].part, 2);
+        assert!(report.generator.min_ns <= report.generator.max_ns);
+    }
+
+    #[test]
+    pub fn test_bench_puzzle_missing_part() {
+        let puzzle = solution::find(25).unwrap();
+        let config = BenchConfig { warmup: 1, iterations: 3 };
+
+        let error = bench_puzzle(puzzle, ">>", &[Part::Two], &config).unwrap_err();
+
+        assert!(matches!(error, BenchError::Solve(SolveError { day: 25, .. })));
+    }
+
+    #[test]
+    pub fn test_report_json_roundtrip() {
+        let stats = Stats { min_ns: 1, median_ns: 2, p90_ns: 3, p99_ns: 4, max_ns: 5 };
+        let report = Report {
+            warmup: 1,
+            iterations: 2,
+            days: vec![DayReport { day: 15, generator: stats, parts: vec![PartReport { part: 1, solver: stats }] }],
+        };
+
+        let parsed = Report::from_json(&report.to_json()).unwrap();
+
+        assert_eq!(parsed, report);
+        assert_eq!(parsed.day(15).unwrap().part(1).unwrap().solver.median_ns, 2);
+    }
+
+    #[test]
+    pub fn test_median_change() {
+        let baseline = Stats { min_ns: 0, median_ns: 200, p90_ns: 0, p99_ns: 0, max_ns: 0 };
+        let current = Stats { median_ns: 150, ..baseline };
+
+        assert_eq!(median_change(&baseline, &current), -0.25);
+    }
+}
@@ -1,51 +1,320 @@
-use std::num::ParseIntError;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 1;
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseIntError> {
+pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    let source = Source::new(DAY, input);
     input
         .lines()
         .filter(|s| !(*s).is_empty())
-        .map(|s| s.parse::<u32>())
-        .collect::<Result<Vec<_>, ParseIntError>>()
+        .map(|s| source.parse::<u32>(s, "depth measurement"))
+        .collect::<Result<Vec<_>, ParseError>>()
+}
+
+/// How the measurements within a sliding window are combined before comparing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Sum,
+    Mean,
+    Median,
+}
+
+impl Aggregation {
+    // Value that orders windows of the same size like the aggregate itself.
+    // Means are sums divided by the same size, and medians are doubled so
+    // that they stay integers for even sizes.
+    fn key(&self, window: &[u32]) -> u64 {
+        match self {
+            Aggregation::Sum | Aggregation::Mean => window.iter().map(|m| *m as u64).sum(),
+            Aggregation::Median => {
+                let mut sorted = window.to_vec();
+                sorted.sort_unstable();
+                let mid = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    sorted[mid - 1] as u64 + sorted[mid] as u64
+                } else {
+                    2 * sorted[mid] as u64
+                }
+            },
+        }
+    }
+
+    // Whether the window of all but the first of `measurements` has a larger
+    // aggregate than the window of all but the last one
+    fn increased(&self, measurements: &[u32]) -> bool {
+        let last = measurements.len() - 1;
+        match self {
+            // Both windows share all measurements in between, so only the first
+            // and the last one have to be compared
+            Aggregation::Sum | Aggregation::Mean => measurements[last] > measurements[0],
+            Aggregation::Median => self.key(&measurements[1..]) > self.key(&measurements[..last]),
+        }
+    }
+}
+
+/// Number of times the sum of a sliding window of `window_size` measurements
+/// is larger than the one of the window before it
+///
+/// # Panics
+///
+/// Panics if `window_size` is 0.
+pub fn count_increases(measurements: &[u32], window_size: usize) -> usize {
+    count_increases_by(measurements, window_size, Aggregation::Sum)
+}
+
+/// Like `count_increases`, but combining the measurements of each window with
+/// the given aggregation. Sums and means only compare two measurements per
+/// step, whereas medians sort both windows at every step, which takes
+/// O(n·k log k) for n measurements and windows of k.
+///
+/// # Panics
+///
+/// Panics if `window_size` is 0.
+pub fn count_increases_by(measurements: &[u32], window_size: usize, aggregation: Aggregation) -> usize {
+    assert!(window_size > 0, "Window size has to be positive");
+
+    measurements
+        .windows(window_size + 1)
+        .filter(|v| aggregation.increased(v))
+        .count()
+}
+
+#[derive(Debug)]
+pub enum ReadingError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadingError::Io(e) => write!(f, "Cannot read measurements: {}", e),
+            ReadingError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ReadingError {}
+
+/// Depth measurements read one line at a time, skipping empty lines the same
+/// way the input generator does. Reading stops after the first I/O error, as
+/// a reader that failed once may keep failing forever.
+pub fn readings<R: BufRead>(reader: R) -> impl Iterator<Item = Result<u32, ReadingError>> {
+    reader
+        .lines()
+        .scan(false, |failed, line| (!*failed).then(|| {
+            *failed = line.is_err();
+            line
+        }))
+        .enumerate()
+        .filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.is_empty()))
+        .map(|(i, line)| {
+            let line = line.map_err(ReadingError::Io)?;
+            Source::at_line(DAY, &line, i + 1)
+                .parse::<u32>(&line, "depth measurement")
+                .map_err(ReadingError::Parse)
+        })
+}
+
+/// Statistics over all measurements seen so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub measurements: usize,
+    /// Number of times a window's aggregate was larger than the previous one's
+    pub increases: usize,
+    /// Most consecutive measurements that are strictly increasing or strictly decreasing
+    pub longest_run: usize,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+/// Computes a `Summary` incrementally, only keeping the measurements of the
+/// last two windows
+#[derive(Debug, Clone)]
+pub struct DepthAnalyzer {
+    window_size: usize,
+    aggregation: Aggregation,
+    recent: VecDeque<u32>,
+    increasing_run: usize,
+    decreasing_run: usize,
+    summary: Summary,
+}
+
+impl DepthAnalyzer {
+    /// # Panics
+    ///
+    /// Panics if `window_size` is 0.
+    pub fn new(window_size: usize, aggregation: Aggregation) -> DepthAnalyzer {
+        assert!(window_size > 0, "Window size has to be positive");
+
+        DepthAnalyzer {
+            window_size,
+            aggregation,
+            recent: VecDeque::with_capacity(window_size + 1),
+            increasing_run: 0,
+            decreasing_run: 0,
+            summary: Summary::default(),
+        }
+    }
+
+    pub fn summary(&self) -> Summary {
+        self.summary
+    }
+
+    /// Adds the next measurement, returning the updated summary
+    pub fn push(&mut self, depth: u32) -> Summary {
+        let (increasing, decreasing) = match self.recent.back() {
+            Some(previous) => (depth > *previous, depth < *previous),
+            None => (false, false),
+        };
+        self.increasing_run = if increasing { self.increasing_run + 1 } else { 1 };
+        self.decreasing_run = if decreasing { self.decreasing_run + 1 } else { 1 };
+
+        if self.recent.len() > self.window_size {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(depth);
+
+        let summary = &mut self.summary;
+        summary.measurements += 1;
+        summary.longest_run = summary.longest_run.max(self.increasing_run).max(self.decreasing_run);
+        summary.min = Some(summary.min.map_or(depth, |min| min.min(depth)));
+        summary.max = Some(summary.max.map_or(depth, |max| max.max(depth)));
+        if self.recent.len() > self.window_size && self.aggregation.increased(self.recent.make_contiguous()) {
+            summary.increases += 1;
+        }
+
+        self.summary
+    }
+}
+
+/// Running summaries of the measurements read from `reader`, one per
+/// measurement, stopping after the first I/O error like `readings`
+pub fn analyze<R: BufRead>(reader: R, window_size: usize, aggregation: Aggregation)
+    -> impl Iterator<Item = Result<Summary, ReadingError>> {
+    let mut analyzer = DepthAnalyzer::new(window_size, aggregation);
+    readings(reader).map(move |reading| reading.map(|depth| analyzer.push(depth)))
 }
 
 #[aoc(day1, part1)]
-pub fn solve_part1(input: &[u32]) -> Result<usize, ParseIntError> {
-    Ok(input
-        .windows(2)
-        .filter(|v| v[1] > v[0])
-        .count())
+pub fn solve_part1(input: &[u32]) -> usize {
+    count_increases(input, 1)
 }
 
 #[aoc(day1, part2)]
-pub fn solve_part2(input: &[u32]) -> Result<usize, ParseIntError> {
-    Ok(input
-        .windows(4)
-        .filter(|v| v[3] > v[0])
-        .count())
+pub fn solve_part2(input: &[u32]) -> usize {
+    count_increases(input, 3)
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = DAY;
+    type Input = Vec<u32>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::io::Read;
     use super::*;
 
+    pub fn sample() -> Vec<u32> {
+        vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]
+    }
+
+    #[test]
+    pub fn test_count_increases() {
+        let counts = (1..=4).map(|size| count_increases(&sample(), size)).collect::<Vec<usize>>();
+
+        assert_eq!(counts, [7, 5, 5, 6]);
+        assert_eq!(count_increases(&sample(), 10), 0);
+        assert_eq!(count_increases(&sample(), 11), 0);
+    }
+
     #[test]
-    pub fn test_solve_part1() {
-        let input = vec![ 199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-        let expected_output = 7;
+    pub fn test_count_increases_by() {
+        let count = |size, aggregation| count_increases_by(&sample(), size, aggregation);
 
-        let output = solve_part1(&input).unwrap();
+        assert_eq!(count(3, Aggregation::Mean), count(3, Aggregation::Sum));
+        assert_eq!((1..=4).map(|size| count(size, Aggregation::Median)).collect::<Vec<usize>>(), [7, 5, 4, 5]);
+        // Doubled medians of large measurements must not overflow
+        assert_eq!(count_increases_by(&[u32::MAX, 0, u32::MAX, u32::MAX], 2, Aggregation::Median), 1);
+    }
+
+    #[test]
+    pub fn test_analyze() {
+        let text = sample().iter().map(|m| format!("{}\n", m)).collect::<String>();
+
+        let summaries = analyze(text.as_bytes(), 3, Aggregation::Sum).collect::<Result<Vec<Summary>, _>>().unwrap();
+
+        assert_eq!(summaries.iter().map(|s| s.increases).collect::<Vec<usize>>(), [0, 0, 0, 1, 1, 1, 2, 3, 4, 5]);
+        assert_eq!(summaries[9], Summary { measurements: 10, increases: 5, longest_run: 4, min: Some(199), max: Some(269) });
+    }
+
+    #[test]
+    pub fn test_depth_analyzer_matches_count_increases() {
+        for size in 1..=4 {
+            let mut analyzer = DepthAnalyzer::new(size, Aggregation::Median);
+            for depth in sample() {
+                analyzer.push(depth);
+                assert!(analyzer.recent.len() <= size + 1);
+            }
+
+            assert_eq!(analyzer.summary().increases, count_increases_by(&sample(), size, Aggregation::Median));
+        }
+    }
+
+    #[test]
+    pub fn test_readings_invalid() {
+        let results = readings("199\n\n2OO\n".as_bytes()).collect::<Vec<_>>();
 
-        assert_eq!(output, expected_output);
+        assert_eq!(results.len(), 2);
+        match &results[1] {
+            Err(ReadingError::Parse(error)) => assert_eq!((error.line, error.token.as_str()), (3, "2OO")),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    struct BrokenReader;
+
+    impl Read for BrokenReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken"))
+        }
     }
 
     #[test]
-    pub fn test_solve_part2() {
-        let input = vec![ 199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-        let expected_output = 5;
+    pub fn test_readings_stop_at_io_error() {
+        let results = readings(io::BufReader::new("199\n".as_bytes().chain(BrokenReader))).collect::<Vec<_>>();
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[1], Err(ReadingError::Io(_))));
+        assert_eq!(analyze(io::BufReader::new(BrokenReader), 1, Aggregation::Sum).count(), 1);
+    }
 
-        let output = solve_part2(&input).unwrap();
+    #[test]
+    pub fn test_input_generator_invalid() {
+        let error = input_generator("199\n2OO\n").unwrap_err();
 
-        assert_eq!(output, expected_output);
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 1, "2OO"));
     }
 }
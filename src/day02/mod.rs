@@ -1,8 +1,11 @@
-use std::num::ParseIntError;
+use std::fmt;
 use std::str::FromStr;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
 
+const DAY: u8 = 2;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Direction {
     Up, Down, Forward
 }
@@ -13,30 +16,48 @@ pub struct Command {
     amount: usize,
 }
 
-impl FromStr for Command {
-    type Err = ParseIntError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let v = s.split(' ').collect::<Vec<&str>>();
-        let direction = match v[0] {
+impl Command {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Command, ParseError> {
+        let (direction_str, amount_str) = source.split_once(s, " ", "' ' followed by amount")?;
+        let direction = match direction_str {
             "forward" => Direction::Forward,
             "up" => Direction::Up,
             "down" => Direction::Down,
-            _ => panic!("Cannot parse '{}' as direction", v[0])
+            _ => return Err(source.error(direction_str, "forward, up or down"))
         };
 
-        let amount = v[1].parse::<usize>();
+        let amount = source.parse::<usize>(amount_str, "amount")?;
+
+        Ok(Command { direction, amount })
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Command::parse(&Source::new(DAY, s), s)
+    }
+}
 
-        Ok(Command { direction, amount: amount? })
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Forward => "forward",
+            Direction::Up => "up",
+            Direction::Down => "down",
+        };
+        write!(f, "{} {}", direction, self.amount)
     }
 }
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Result<Vec<Command>, ParseIntError> {
+pub fn input_generator(input: &str) -> Result<Vec<Command>, ParseError> {
+    let source = Source::new(DAY, input);
     input
         .lines()
         .filter(|s| !(*s).is_empty())
-        .map(|s| Command::from_str(s))
-        .collect::<Result<Vec<Command>, ParseIntError>>()
+        .map(|s| Command::parse(&source, s))
+        .collect::<Result<Vec<Command>, ParseError>>()
 }
 
 #[aoc(day2, part1)]
@@ -85,33 +106,67 @@ pub fn solve_part2(input: &[Command]) -> usize {
     depth * distance
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Command>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     pub fn sample_str() -> String {
         String::from("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n")
     }
 
     #[test]
-    pub fn test_solve_part1() {
-        let input = input_generator(sample_str().as_str()).unwrap();
-        let expected_output = 150;
+    pub fn test_input_generator() {
+        let commands = input_generator(sample_str().as_str()).unwrap();
 
-        let output = solve_part1(&input);
+        assert_eq!(commands.len(), 6);
+        assert!(matches!(commands[3], Command { direction: Direction::Up, amount: 3 }));
+    }
 
-        assert_eq!(output, expected_output);
+    #[test]
+    pub fn test_input_generator_unknown_direction() {
+        let error = input_generator("forward 5\nsideways 3\n").unwrap_err();
+
+        assert_eq!(error.to_string(),
+            "day 2, line 2, column 1: expected forward, up or down, found \"sideways\"");
     }
 
+    fn command() -> impl Strategy<Value = Command> {
+        let direction = prop_oneof![Just(Direction::Forward), Just(Direction::Up), Just(Direction::Down)];
+        (direction, any::<usize>()).prop_map(|(direction, amount)| Command { direction, amount })
+    }
 
-    #[test]
-    pub fn test_solve_part2() {
-        let input = input_generator(sample_str().as_str()).unwrap();
-        let expected_output = 900;
+    proptest! {
+        #[test]
+        fn test_command_roundtrip(command in command()) {
+            let formatted = command.to_string();
 
-        let output = solve_part2(&input);
+            prop_assert_eq!(Command::from_str(&formatted).unwrap().to_string(), formatted);
+        }
 
-        assert_eq!(output, expected_output);
+        #[test]
+        fn test_command_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..32)) {
+            let _ = Command::from_str(&String::from_utf8_lossy(&bytes));
+        }
     }
 }
@@ -1,10 +1,38 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 3;
+
 #[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Vec<String> {
-    input
+pub fn input_generator(input: &str) -> Result<Vec<String>, ParseError> {
+    let source = Source::new(DAY, input);
+    let lines = input
         .lines()
         .filter(|s| !(*s).is_empty())
-        .map(|s| s.to_owned())
-        .collect::<Vec<String>>()
+        .collect::<Vec<&str>>();
+
+    let bitcount = match lines.first() {
+        Some(first) => first.len(),
+        None => return Err(source.error(input, "binary number")),
+    };
+
+    for line in &lines {
+        if let Some((pos, c)) = line.char_indices().find(|(_, c)| *c != '0' && *c != '1') {
+            return Err(source.error(&line[pos..pos + c.len_utf8()], "binary digit"));
+        }
+        // All numbers need to have the same width as the first one
+        if line.len() < bitcount {
+            return Err(source.error(&line[line.len()..], "binary digit"));
+        }
+        if line.len() > bitcount {
+            return Err(source.error(&line[bitcount..], "end of line"));
+        }
+    }
+
+    Ok(lines
+        .iter()
+        .map(|s| (*s).to_owned())
+        .collect::<Vec<String>>())
 }
 
 #[aoc(day3, part1)]
@@ -93,6 +121,26 @@ pub fn get_co2_scrubber_rating(input: &[String]) -> usize {
     panic!("No oxygen generator rating found (remaining input: {:?})", remaining);
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = DAY;
+    type Input = Vec<String>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -113,19 +161,9 @@ mod test {
 ")
     }
 
-    #[test]
-    pub fn test_solve_part1() {
-        let input = input_generator(sample_str().as_str());
-        let expected_output = 198;
-
-        let output = solve_part1(&input);
-
-        assert_eq!(output, expected_output);
-    }
-
     #[test]
     pub fn test_get_oxygen_generator_rating() {
-        let input = input_generator(sample_str().as_str());
+        let input = input_generator(sample_str().as_str()).unwrap();
         let expected_output = 23;
 
         let output = get_oxygen_generator_rating(&input);
@@ -135,7 +173,7 @@ mod test {
 
     #[test]
     pub fn test_get_co2_scrubber_rating() {
-        let input = input_generator(sample_str().as_str());
+        let input = input_generator(sample_str().as_str()).unwrap();
         let expected_output = 10;
 
         let output = get_co2_scrubber_rating(&input);
@@ -144,12 +182,9 @@ mod test {
     }
 
     #[test]
-    pub fn test_solve_part2() {
-        let input = input_generator(sample_str().as_str());
-        let expected_output = 230;
-
-        let output = solve_part2(&input);
+    pub fn test_input_generator_truncated_line() {
+        let error = input_generator("00100\n1111\n").unwrap_err();
 
-        assert_eq!(output, expected_output);
+        assert_eq!((error.line, error.column, error.expected), (2, 5, "binary digit"));
     }
 }
@@ -1,5 +1,10 @@
+use std::fmt;
 use std::str::FromStr;
-use std::num::ParseIntError;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 4;
+const BOARD_SIZE: usize = 5;
 
 #[derive(Debug)]
 struct Outcome {
@@ -49,22 +54,56 @@ impl Board {
     }
     }
 
-impl FromStr for Board {
-    type Err = ParseIntError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rows = s
-            .lines()
-            .map(|row| row
+impl Board {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Board, ParseError> {
+        let mut rows = Vec::new();
+        for row in s.lines().filter(|row| !row.trim().is_empty()) {
+            let numbers = row
                 .split(' ')
                 .filter(|x| !(*x).is_empty())
-                .map(|x| x.parse::<u32>().unwrap())
-                .collect()
-                )
-            .collect();
+                .collect::<Vec<&str>>();
+
+            if rows.len() == BOARD_SIZE {
+                return Err(source.error(row, "blank line after board"));
+            }
+            if numbers.len() < BOARD_SIZE {
+                return Err(source.error(&row[row.len()..], "board number"));
+            }
+            if numbers.len() > BOARD_SIZE {
+                return Err(source.error(numbers[BOARD_SIZE], "end of row"));
+            }
+
+            rows.push(numbers
+                .iter()
+                .map(|x| source.parse::<u32>(x, "board number"))
+                .collect::<Result<Vec<u32>, ParseError>>()?);
+        }
+
+        if rows.len() < BOARD_SIZE {
+            return Err(source.error(&s[s.len()..], "board row"));
+        }
+
         Ok(Board { rows } )
     }
 }
 
+impl FromStr for Board {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::parse(&Source::new(DAY, s), s)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.rows {
+            let numbers = row.iter().map(|x| format!("{:>2}", x)).collect::<Vec<String>>();
+            writeln!(f, "{}", numbers.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Game {
     draw: Vec<u32>,
@@ -74,8 +113,7 @@ pub struct Game {
 impl Game {
     fn play_boards(&self) -> Vec<Outcome> {
         let mut outcomes = self.boards.iter()
-            .map(|board| board.play(&self.draw))
-            .flatten()
+            .filter_map(|board| board.play(&self.draw))
             .collect::<Vec<Outcome>>();
 
             outcomes.sort_by(|a, b| a.winning_round.partial_cmp(&b.winning_round).unwrap());
@@ -85,20 +123,25 @@ impl Game {
 }
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Game {
+pub fn input_generator(input: &str) -> Result<Game, ParseError> {
+    let source = Source::new(DAY, input);
     let groups = input.split("\n\n").collect::<Vec<&str>>();
 
     let draw = groups[0]
         .split(',')
-        .map(|x| x.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
+        .map(|x| source.parse::<u32>(x, "drawn number"))
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+
+    if groups.len() < 2 {
+        return Err(source.error(&input[input.len()..], "blank line followed by boards"));
+    }
 
     let boards = groups[1..]
         .iter()
-        .map(|x| Board::from_str(x).unwrap())
-        .collect::<Vec<Board>>();
+        .map(|x| Board::parse(&source, x))
+        .collect::<Result<Vec<Board>, ParseError>>()?;
 
-    Game { draw, boards }
+    Ok(Game { draw, boards })
 }
 
 #[aoc(day4, part1)]
@@ -115,9 +158,30 @@ pub fn solve_part2(game: &Game) -> u32 {
     loser.score
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = DAY;
+    type Input = Game;
+    type Output = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     pub fn sample_str() -> String {
         String::from("7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
@@ -144,29 +208,37 @@ mod test {
 
     #[test]
     pub fn test_input_generator() {
-        let game = input_generator(sample_str().as_str());
+        let game = input_generator(sample_str().as_str()).unwrap();
 
         assert_eq!(game.draw[2], 9);
         assert_eq!(game.boards[1].rows[1][2], 13);
     }
 
     #[test]
-    pub fn test_solve_part1() {
-        let game = input_generator(sample_str().as_str());
-        let expected_score = 4512;
+    pub fn test_input_generator_truncated_board() {
+        let truncated = &sample_str()[..sample_str().len() - 31];
 
-        let score = solve_part1(&game);
+        let error = input_generator(truncated).unwrap_err();
 
-        assert_eq!(score, expected_score);
+        assert_eq!(error.to_string(), "day 4, line 17, column 15: expected board row, found nothing");
     }
 
-    #[test]
-    pub fn test_solve_part2() {
-        let game = input_generator(sample_str().as_str());
-        let expected_score = 1924;
+    fn board() -> impl Strategy<Value = Board> {
+        prop::collection::vec(prop::collection::vec(0..100u32, BOARD_SIZE), BOARD_SIZE)
+            .prop_map(|rows| Board { rows })
+    }
+
+    proptest! {
+        #[test]
+        fn test_board_roundtrip(board in board()) {
+            let formatted = board.to_string();
 
-        let score = solve_part2(&game);
+            prop_assert_eq!(Board::from_str(&formatted).unwrap().to_string(), formatted);
+        }
 
-        assert_eq!(score, expected_score);
+        #[test]
+        fn test_board_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..128)) {
+            let _ = Board::from_str(&String::from_utf8_lossy(&bytes));
+        }
     }
 }
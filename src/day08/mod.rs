@@ -1,7 +1,13 @@
+use std::fmt;
 use std::str::FromStr;
-use std::string::ParseError;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 8;
+const UNIQUES_COUNT: usize = 10;
+const OUTPUT_COUNT: usize = 4;
 
 type Digit = BTreeSet<char>;
 
@@ -11,27 +17,64 @@ pub struct Entry {
     output: Vec<Digit>,
 }
 
+fn parse_digits<'a>(source: &Source<'a>, s: &'a str, count: usize, expected_after: &'static str)
+    -> Result<Vec<Digit>, ParseError> {
+    let words = s.split(' ')
+        .filter(|s| !(*s).is_empty())
+        .collect::<Vec<&str>>();
+
+    if words.len() < count {
+        return Err(source.error(&s[s.len()..], "signal pattern"));
+    }
+    if words.len() > count {
+        return Err(source.error(words[count], expected_after));
+    }
+
+    words
+        .iter()
+        .map(|word| match word.char_indices().find(|(_, c)| !('a'..='g').contains(c)) {
+            Some((pos, c)) => Err(source.error(&word[pos..pos + c.len_utf8()], "segment a-g")),
+            None => Ok(Digit::from_iter(word.chars())),
+        })
+        .collect::<Result<Vec<Digit>, ParseError>>()
+}
+
+impl Entry {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Entry, ParseError> {
+        let (left, right) = source.split_once(s, "|", "'|' separator")?;
+
+        let uniques = parse_digits(source, left, UNIQUES_COUNT, "'|' separator")?;
+        let output = parse_digits(source, right, OUTPUT_COUNT, "end of line")?;
+
+        Ok(Entry { uniques, output } )
+    }
+}
+
 impl FromStr for Entry {
     type Err = ParseError;
     fn from_str(s: &str) -> Result<Entry, Self::Err> {
-        let lr = s.split('|')
-            .map(|lr| lr.split(' ')
-                .filter(|s| !(*s).is_empty())
-                .map(|d| Digit::from_iter(d.chars()))
-                .collect::<Vec<Digit>>()
-            )
-            .collect::<Vec<Vec<Digit>>>();
-
-        Ok(Entry { uniques: lr[0].to_vec(), output: lr[1].to_vec() } )
+        Entry::parse(&Source::new(DAY, s), s)
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let words = |digits: &[Digit]| digits
+            .iter()
+            .map(|digit| digit.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join(" ");
+        write!(f, "{} | {}", words(&self.uniques), words(&self.output))
     }
 }
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<Entry> {
+pub fn input_generator(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let source = Source::new(DAY, input);
     input
         .lines()
-        .map(|x| Entry::from_str(x).unwrap())
-        .collect::<Vec<Entry>>()
+        .map(|x| Entry::parse(&source, x))
+        .collect::<Result<Vec<Entry>, ParseError>>()
 }
 
 #[aoc(day8, part1)]
@@ -136,9 +179,31 @@ pub fn solve_part2(entries: &[Entry]) -> usize {
         .sum::<usize>()
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Entry>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     pub fn sample_str() -> String {
         String::from("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
@@ -155,22 +220,12 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 
     #[test]
     pub fn test_input_generator() {
-        let entries = input_generator(&sample_str().as_str());
+        let entries = input_generator(&sample_str().as_str()).unwrap();
 
         assert_eq!(entries[0].uniques[0], BTreeSet::from_iter(['b', 'e']));
         assert_eq!(entries[1].output[3], BTreeSet::from_iter(['g', 'c']));
     }
 
-    #[test]
-    pub fn test_solve_part1() {
-        let entries = input_generator(&sample_str().as_str());
-        let expected_count = 26;
-
-        let count = solve_part1(&entries);
-
-        assert_eq!(count, expected_count);
-    }
-
     #[test]
     pub fn test_solve_entry() {
         let entry = Entry::from_str("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf").unwrap();
@@ -181,12 +236,29 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
     }
 
     #[test]
-    pub fn test_solve_part2() {
-        let entries = input_generator(&sample_str().as_str());
-        let expected_sum = 61229;
+    pub fn test_entry_from_str_missing_separator() {
+        let error = Entry::from_str("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab cdfeb").unwrap_err();
 
-        let sum = solve_part2(&entries);
+        assert_eq!(error.to_string(), "day 8, line 1, column 65: expected '|' separator, found nothing");
+    }
+
+    fn entry() -> impl Strategy<Value = Entry> {
+        let digit = prop::collection::btree_set(prop::char::range('a', 'g'), 1..=7);
+        (prop::collection::vec(digit.clone(), UNIQUES_COUNT), prop::collection::vec(digit, OUTPUT_COUNT))
+            .prop_map(|(uniques, output)| Entry { uniques, output })
+    }
 
-        assert_eq!(sum, expected_sum);
+    proptest! {
+        #[test]
+        fn test_entry_roundtrip(entry in entry()) {
+            let formatted = entry.to_string();
+
+            prop_assert_eq!(Entry::from_str(&formatted).unwrap().to_string(), formatted);
+        }
+
+        #[test]
+        fn test_entry_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..128)) {
+            let _ = Entry::from_str(&String::from_utf8_lossy(&bytes));
+        }
     }
 }
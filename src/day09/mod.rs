@@ -0,0 +1,129 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+const DAY: u8 = 9;
+const WALL: u8 = 9;
+
+#[aoc_generator(day9)]
+pub fn input_generator(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_digits(&Source::new(DAY, input), input, "height digit")
+}
+
+/// Coordinates of all locations lower than each of their adjacent locations
+pub fn low_points(map: &Grid<u8>) -> Vec<(usize, usize)> {
+    map.iter()
+        .filter(|((x, y), height)| map.neighbors4(*x, *y).all(|neighbor| map[neighbor] > **height))
+        .map(|(coords, _)| coords)
+        .collect()
+}
+
+/// Partition of a height map into basins, separated by walls of height 9
+#[derive(Debug)]
+pub struct Basins {
+    /// Basin number of every location, `None` for walls
+    pub labels: Grid<Option<usize>>,
+    /// Number of locations per basin, indexed by basin number
+    pub sizes: Vec<usize>,
+}
+
+/// Labels every basin with a flood fill, numbering them in row-major order of
+/// their first location
+pub fn basins(map: &Grid<u8>) -> Basins {
+    let mut labels = Grid::new(map.width(), map.height(), None);
+    let mut sizes = Vec::new();
+
+    for (start, height) in map.iter() {
+        if *height == WALL || labels[start].is_some() {
+            continue;
+        }
+
+        let label = sizes.len();
+        let mut size = 0;
+        let mut stack = vec![start];
+        labels[start] = Some(label);
+
+        while let Some((x, y)) = stack.pop() {
+            size += 1;
+            for neighbor in map.neighbors4(x, y) {
+                if map[neighbor] != WALL && labels[neighbor].is_none() {
+                    labels[neighbor] = Some(label);
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        sizes.push(size);
+    }
+
+    Basins { labels, sizes }
+}
+
+#[aoc(day9, part1)]
+pub fn solve_part1(map: &Grid<u8>) -> usize {
+    low_points(map)
+        .iter()
+        .map(|coords| map[*coords] as usize + 1)
+        .sum()
+}
+
+#[aoc(day9, part2)]
+pub fn solve_part2(map: &Grid<u8>) -> usize {
+    let mut sizes = basins(map).sizes;
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+    sizes.iter().take(3).product()
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = DAY;
+    type Input = Grid<u8>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub fn sample_str() -> String {
+        String::from("2199943210
+3987894921
+9856789892
+8767896789
+9899965678")
+    }
+
+    #[test]
+    pub fn test_low_points() {
+        let map = input_generator(sample_str().as_str()).unwrap();
+
+        assert_eq!(low_points(&map), vec![(1, 0), (9, 0), (2, 2), (6, 4)]);
+    }
+
+    #[test]
+    pub fn test_basins() {
+        let map = input_generator(sample_str().as_str()).unwrap();
+
+        let basins = basins(&map);
+
+        assert_eq!(basins.sizes, vec![3, 9, 14, 9]);
+        assert_eq!(basins.labels[(0, 0)], Some(0));
+        assert_eq!(basins.labels[(2, 0)], None);
+        assert_eq!(basins.labels[(9, 0)], Some(1));
+        assert_eq!(basins.labels[(6, 4)], Some(3));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 10;
+const BRACKETS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// Outcome of checking the brackets of a single line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineStatus {
+    Valid,
+    /// A closing bracket that does not match the innermost open chunk, with its
+    /// 1-based column
+    Corrupted { found: char, column: usize },
+    /// All chunks match, but some are left open and need `completion` to be closed
+    Incomplete { completion: String },
+}
+
+fn closing(open: char) -> Option<char> {
+    BRACKETS.iter().find(|(o, _)| *o == open).map(|(_, c)| *c)
+}
+
+#[aoc_generator(day10)]
+pub fn input_generator(input: &str) -> Result<Vec<String>, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|line| match line.char_indices().find(|(_, c)| !BRACKETS.iter().any(|(o, cl)| c == o || c == cl)) {
+            Some((pos, c)) => Err(source.error(&line[pos..pos + c.len_utf8()], "bracket")),
+            None => Ok(line.to_owned()),
+        })
+        .collect::<Result<Vec<String>, ParseError>>()
+}
+
+pub fn check_line(line: &str) -> LineStatus {
+    let mut expected_closings = Vec::new();
+
+    for (column, c) in line.chars().enumerate() {
+        if let Some(closing) = closing(c) {
+            expected_closings.push(closing);
+        } else if expected_closings.pop() != Some(c) {
+            return LineStatus::Corrupted { found: c, column: column + 1 };
+        }
+    }
+
+    if expected_closings.is_empty() {
+        LineStatus::Valid
+    } else {
+        LineStatus::Incomplete { completion: expected_closings.iter().rev().collect() }
+    }
+}
+
+fn syntax_error_score(illegal: char) -> u64 {
+    match illegal {
+        ')' => 3,
+        ']' => 57,
+        '}' => 1197,
+        '>' => 25137,
+        _ => 0,
+    }
+}
+
+// None if the score does not fit in a u64, which takes a completion of more
+// than 27 characters
+fn autocomplete_score(completion: &str) -> Option<u64> {
+    completion
+        .chars()
+        .map(|c| match c {
+            ')' => 1,
+            ']' => 2,
+            '}' => 3,
+            '>' => 4,
+            _ => 0,
+        })
+        .try_fold(0u64, |score, points| score.checked_mul(5)?.checked_add(points))
+}
+
+#[aoc(day10, part1)]
+pub fn solve_part1(lines: &[String]) -> u64 {
+    lines
+        .iter()
+        .map(|line| match check_line(line) {
+            LineStatus::Corrupted { found, .. } => syntax_error_score(found),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[aoc(day10, part2)]
+pub fn solve_part2(lines: &[String]) -> Result<u64, SolveError> {
+    let mut scores = lines
+        .iter()
+        .filter_map(|line| match check_line(line) {
+            LineStatus::Incomplete { completion } => Some(autocomplete_score(&completion)),
+            _ => None,
+        })
+        .collect::<Option<Vec<u64>>>()
+        .ok_or_else(|| SolveError::new(DAY, "autocomplete score does not fit in 64 bits"))?;
+    scores.sort_unstable();
+
+    scores.get(scores.len() / 2).copied().ok_or_else(|| SolveError::new(DAY, "no line is incomplete"))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = DAY;
+    type Input = Vec<String>;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_check_line() {
+        assert_eq!(check_line("([])"), LineStatus::Valid);
+        assert_eq!(check_line("{([(<{}[<>[]}>{[]{[(<()>"), LineStatus::Corrupted { found: '}', column: 13 });
+        assert_eq!(check_line("<{([([[(<>()){}]>(<<{{"), LineStatus::Corrupted { found: '>', column: 17 });
+        assert_eq!(check_line("[({(<(())[]>[[{[]{<()<>>"),
+            LineStatus::Incomplete { completion: String::from("}}]])})]") });
+    }
+
+    #[test]
+    pub fn test_autocomplete_score() {
+        assert_eq!(autocomplete_score("}}]])})]"), Some(288957));
+        assert_eq!(autocomplete_score("])}>"), Some(294));
+        assert_eq!(autocomplete_score(&">".repeat(27)), Some(5u64.pow(27) - 1));
+        assert_eq!(autocomplete_score(&">".repeat(28)), None);
+    }
+
+    #[test]
+    pub fn test_solve_part2_long_line() {
+        let lines = vec!["<".repeat(40)];
+
+        let error = solve_part2(&lines).unwrap_err();
+
+        assert_eq!(error.to_string(), "day 10: autocomplete score does not fit in 64 bits");
+    }
+
+    #[test]
+    pub fn test_solve_part2_no_incomplete_line() {
+        let lines = vec![String::from("[]"), String::from("{()()()>")];
+
+        assert_eq!(solve_part2(&lines).unwrap_err().to_string(), "day 10: no line is incomplete");
+    }
+
+    #[test]
+    pub fn test_input_generator_invalid() {
+        let error = input_generator("[({(<(())[]>[[{[]{<()<>>\n(((x").unwrap_err();
+
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 4, "x"));
+    }
+}
@@ -1,94 +1,66 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+const DAY: u8 = 11;
+
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|l| l.chars()
-            .map(|c| c.to_digit(10).unwrap() as u8)
-            .collect::<Vec<u8>>()
-        )
-        .collect::<Vec<Vec<u8>>>()
+pub fn input_generator(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_digits(&Source::new(DAY, input), input, "energy level digit")
 }
 
-fn increment(matrix: &mut Vec<Vec<u8>>) {
-    for row in matrix {
-        for col in row {
-            *col += 1;
-        }
+fn increment(matrix: &mut Grid<u8>) {
+    for cell in matrix.cells_mut() {
+        *cell += 1;
     }
 }
 
-fn increment_neighbors(matrix: &mut Vec<Vec<u8>>, row_idx: &usize, col_idx: &usize) {
-    let neighbors = vec![
-        ( 0,  1),  (1, 1),  (1,  0),
-        ( 1, -1),           (0, -1),
-        (-1, -1), (-1, 0), (-1,  1)
-    ].iter()
-        .map(|(r, c)|
-            (*row_idx as i32 + r, *col_idx as i32 + c))
-        .collect::<Vec<(i32, i32)>>();
-
-    let rows_count = matrix.len();
-    let cols_count = matrix[0].len();
-    neighbors
-        .iter()
-        .filter(|(r, c)|
-            r >= &0
-            && c >= &0
-            && r < &(rows_count as i32)
-            && c < &(cols_count as i32))
-        .for_each(|(r, c)|
-            matrix[*r as usize][*c as usize] += 1
-            );
-}
+fn increment_neighbors(matrix: &mut Grid<u8>, x: usize, y: usize) {
+    let neighbors = matrix.neighbors8(x, y).collect::<Vec<(usize, usize)>>();
 
-fn find_over_nine(matrix: &[Vec<u8>]) -> Option<(usize, usize)> {
-    for (row_idx, row) in matrix.iter().enumerate() {
-        for (col_idx, col) in row.iter().enumerate() {
-            if *col > 9 {
-                return Some((row_idx, col_idx));
-            }
-        }
+    for neighbor in neighbors {
+        matrix[neighbor] += 1;
     }
+}
 
-    None
+fn find_over_nine(matrix: &Grid<u8>) -> Option<(usize, usize)> {
+    matrix
+        .iter()
+        .find(|(_, value)| **value > 9)
+        .map(|(coords, _)| coords)
 }
 
-fn step(matrix: &mut Vec<Vec<u8>>) -> usize {
-    let mut flashed = [[false; 10]; 10];
+fn step(matrix: &mut Grid<u8>) -> usize {
+    let mut flashed = Grid::new(matrix.width(), matrix.height(), false);
 
     increment(matrix);
 
     while let Some(over_nine) = find_over_nine(matrix) {
-        let (row_idx, col_idx) = over_nine;
+        let (x, y) = over_nine;
 
         // Don't flash twice
-        if !flashed[row_idx][col_idx] {
-            increment_neighbors(matrix, &row_idx, &col_idx);
-            flashed[row_idx][col_idx] = true;
+        if !flashed[over_nine] {
+            increment_neighbors(matrix, x, y);
+            flashed[over_nine] = true;
         }
 
-        matrix[row_idx][col_idx] = 0;
+        matrix[over_nine] = 0;
     }
 
-    for (row_idx, row) in flashed.iter().enumerate() {
-        for (col_idx, _) in row.iter().enumerate() {
-            if flashed[row_idx][col_idx] {
-                matrix[row_idx][col_idx] = 0;
-            }
+    for (coords, has_flashed) in flashed.iter() {
+        if *has_flashed {
+            matrix[coords] = 0;
         }
     }
 
     flashed
-        .iter()
-        .map(|row| row
-            .iter()
-            .filter(|x| **x)
-            .count()
-        ).sum()
+        .cells()
+        .filter(|x| **x)
+        .count()
 }
 
 #[aoc(day11, part1)]
-pub fn solve_part1(matrix_ro: &[Vec<u8>]) -> usize {
+pub fn solve_part1(matrix_ro: &Grid<u8>) -> usize {
     let mut matrix = matrix_ro.to_owned();
 
     (0..100)
@@ -97,11 +69,11 @@ pub fn solve_part1(matrix_ro: &[Vec<u8>]) -> usize {
 }
 
 #[aoc(day11, part2)]
-pub fn solve_part2(matrix_ro: &[Vec<u8>]) -> usize {
+pub fn solve_part2(matrix_ro: &Grid<u8>) -> usize {
     let mut matrix = matrix_ro.to_owned();
 
     for n in 1.. {
-        if step(&mut matrix) == 100 {
+        if step(&mut matrix) == matrix.len() {
             return n;
         }
     }
@@ -110,7 +82,28 @@ pub fn solve_part2(matrix_ro: &[Vec<u8>]) -> usize {
     0
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = DAY;
+    type Input = Grid<u8>;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod test {
     use super::*;
 
@@ -129,20 +122,20 @@ mod test {
 
     #[test]
     pub fn test_input_generator() {
-        let matrix = input_generator(&sample_str().as_str());
+        let matrix = input_generator(&sample_str().as_str()).unwrap();
 
-        assert_eq!(matrix[0][0], 5);
-        assert_eq!(matrix[1][0], 2);
-        assert_eq!(matrix[0][1], 4);
+        assert_eq!(matrix[(0, 0)], 5);
+        assert_eq!(matrix[(0, 1)], 2);
+        assert_eq!(matrix[(1, 0)], 4);
     }
 
     #[test]
     pub fn test_increment() {
-        let mut matrix = input_generator("123\n456\n789");
+        let mut matrix = input_generator("123\n456\n789").unwrap();
 
         increment(&mut matrix);
 
-        assert_eq!(matrix, vec![vec![2,3,4], vec![5,6,7], vec![8, 9, 10]]);
+        assert_eq!(matrix, Grid::from_rows(vec![vec![2,3,4], vec![5,6,7], vec![8, 9, 10]]).unwrap());
     }
 
     #[test]
@@ -151,18 +144,18 @@ mod test {
 19991
 19191
 19991
-11111");
+11111").unwrap();
         let expected_after_step1 = input_generator("34543
 40004
 50005
 40004
-34543");
+34543").unwrap();
 
         let expected_after_step2 = input_generator("45654
 51115
 61116
 51115
-45654");
+45654").unwrap();
 
         assert_eq!(step(&mut matrix), 9);
 
@@ -172,22 +165,4 @@ mod test {
 
         assert_eq!(matrix, expected_after_step2);
     }
-
-    #[test]
-    pub fn test_solve_part1() {
-        let matrix = input_generator(&sample_str().as_str());
-
-        let flashcount = solve_part1(&matrix);
-
-        assert_eq!(flashcount, 1656);
-    }
-
-    #[test]
-    pub fn test_solve_part2() {
-        let matrix = input_generator(&sample_str().as_str());
-
-        let flashcount = solve_part2(&matrix);
-
-        assert_eq!(flashcount, 195);
-    }
 }
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::BTreeSet;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 12;
 
 #[derive(Debug)]
 pub struct Cave {
@@ -17,11 +21,21 @@ fn insert_or_get(map: &mut Map, id: &str) {
     }
 }
 
+fn parse_cave_id<'a>(source: &Source<'a>, id: &'a str) -> Result<&'a str, ParseError> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(source.error(id, "cave name"));
+    }
+    Ok(id)
+}
+
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> HashMap<String, Cave> {
+pub fn input_generator(input: &str) -> Result<HashMap<String, Cave>, ParseError> {
+    let source = Source::new(DAY, input);
     let mut map: Map = Map::new();
     for line in input.lines() {
-        let (left_id, right_id) = line.split_once('-').unwrap();
+        let (left_id, right_id) = source.split_once(line, "-", "'-' separator")?;
+        let left_id = parse_cave_id(&source, left_id)?;
+        let right_id = parse_cave_id(&source, right_id)?;
 
         insert_or_get(&mut map, left_id);
         insert_or_get(&mut map, right_id);
@@ -29,17 +43,20 @@ pub fn input_generator(input: &str) -> HashMap<String, Cave> {
         map.get_mut(left_id).unwrap().adjacents.insert(right_id.to_owned());
         map.get_mut(right_id).unwrap().adjacents.insert(left_id.to_owned());
     }
-    map
+
+    if !map.contains_key("start") {
+        return Err(source.error(&input[input.len()..], "connection from start"));
+    }
+
+    Ok(map)
 }
 
 fn find_paths(map: &Map, cave_allowed_twice: Option<&str>) -> Vec::<Vec::<String>> {
     let mut known_paths: Vec::<Vec::<String>> = Vec::new();
     let mut paths_to_continue = vec![vec!["start".to_owned()]];
 
-    while !paths_to_continue.is_empty() {
-        // Take next path
-        let path =paths_to_continue.pop().unwrap();
-
+    // Take next path
+    while let Some(path) = paths_to_continue.pop() {
         let current_cave_id = path.last().unwrap();
         if current_cave_id == "end" {
             known_paths.push(path);
@@ -92,8 +109,28 @@ pub fn solve_part2(map: &Map) -> usize {
     known_paths.len()
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = DAY;
+    type Input = Map;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod test {
     use super::*;
 
@@ -120,30 +157,9 @@ kj-HN
 kj-dc")
     }
 
-    pub fn sample3() -> String {
-        String::from("fs-end
-he-DX
-fs-he
-start-DX
-pj-DX
-end-zg
-zg-sl
-zg-pj
-pj-he
-RW-he
-fs-DX
-pj-RW
-zg-RW
-start-pj
-he-WI
-zg-he
-pj-fs
-start-RW")
-    }
-
     #[test]
     pub fn test_input_generator_sample1() {
-        let map = input_generator(&sample1().as_str());
+        let map = input_generator(&sample1().as_str()).unwrap();
 
         assert_eq!(map.get("start").unwrap().adjacents,
             HashSet::from_iter(["A", "b"].map(String::from)));
@@ -155,7 +171,7 @@ start-RW")
 
     #[test]
     pub fn test_input_generator_sample2() {
-        let map = input_generator(&sample2().as_str());
+        let map = input_generator(&sample2().as_str()).unwrap();
 
         assert_eq!(map.get("start").unwrap().adjacents,
             HashSet::from_iter(["HN", "kj", "dc"].map(String::from)));
@@ -166,56 +182,9 @@ start-RW")
     }
 
     #[test]
-    pub fn test_solve_part1_sample1() {
-        let map = input_generator(&sample1().as_str());
-
-        let paths = solve_part1(&map);
-
-        assert_eq!(paths, 10);
-    }
-
-    #[test]
-    pub fn test_solve_part1_sample2() {
-        let map = input_generator(&sample2().as_str());
-
-        let paths = solve_part1(&map);
-
-        assert_eq!(paths, 19);
-    }
-
-    #[test]
-    pub fn test_solve_part1_sample3() {
-        let map = input_generator(&sample3().as_str());
-
-        let paths = solve_part1(&map);
-
-        assert_eq!(paths, 226);
-    }
-
-    #[test]
-    pub fn test_solve_part2_sample1() {
-        let map = input_generator(&sample1().as_str());
-
-        let paths = solve_part2(&map);
-
-        assert_eq!(paths, 36);
-    }
-
-    #[test]
-    pub fn test_solve_part2_sample2() {
-        let map = input_generator(&sample2().as_str());
-
-        let paths = solve_part2(&map);
-
-        assert_eq!(paths, 103);
-    }
-
-    #[test]
-    pub fn test_solve_part2_sample3() {
-        let map = input_generator(&sample3().as_str());
-
-        let paths = solve_part2(&map);
+    pub fn test_input_generator_missing_separator() {
+        let error = input_generator("start-A\nA end").unwrap_err();
 
-        assert_eq!(paths, 3509);
+        assert_eq!((error.line, error.column, error.expected), (2, 6, "'-' separator"));
     }
 }
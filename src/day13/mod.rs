@@ -1,5 +1,14 @@
+use std::fmt;
 use std::str::FromStr;
 use std::collections::HashSet;
+use crate::error::{ParseError, SolveError, Source};
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+const DAY: u8 = 13;
+/// Largest paper `format_dots` renders, plenty for the letters that the dots
+/// spell out once folded
+const MAX_PAPER_CELLS: usize = 1 << 20;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Fold {
@@ -7,16 +16,31 @@ pub enum Fold {
     Y(usize),
 }
 
-impl FromStr for Fold {
-    type Err = std::num::ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (statement, value_str) = s.split_once('=').unwrap();
-        let value = value_str.parse::<usize>().unwrap();
+impl Fold {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Fold, ParseError> {
+        let (statement, value_str) = source.split_once(s, "=", "'=' followed by fold position")?;
+        let value = source.parse::<usize>(value_str, "fold position")?;
         match statement {
             "fold along x" => Ok(Fold::X(value)),
             "fold along y" => Ok(Fold::Y(value)),
-            _ => panic!("Unknown fold instruction"),
+            _ => Err(source.error(statement, "fold along x or fold along y")),
+        }
+    }
+}
+
+impl FromStr for Fold {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Fold::parse(&Source::new(DAY, s), s)
+    }
+}
+
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fold::X(x) => write!(f, "fold along x={}", x),
+            Fold::Y(y) => write!(f, "fold along y={}", y),
         }
     }
 }
@@ -27,39 +51,59 @@ pub struct Coords {
     y: usize,
 }
 
+impl Coords {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Coords, ParseError> {
+        let (x_str, y_str) = source.split_once(s, ",", "',' followed by y coordinate")?;
+        let x = source.parse::<usize>(x_str, "x coordinate")?;
+        let y = source.parse::<usize>(y_str, "y coordinate")?;
+
+        Ok(Coords { x, y })
+    }
+}
+
 impl FromStr for Coords {
-    type Err = std::num::ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nums = s
-            .split(',')
-            .map(|x| (x.parse::<usize>().unwrap()))
-            .collect::<Vec<usize>>();
+        Coords::parse(&Source::new(DAY, s), s)
+    }
+}
 
-        Ok(Coords { x: nums[0], y: nums[1] })
+impl fmt::Display for Coords {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
     }
 }
 
+#[derive(Debug)]
 pub struct Input {
     dots: Vec<Coords>,
     folds: Vec<Fold>,
 }
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Input {
-    let (coords_str, folds_str) = input.split_once("\n\n").unwrap();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let (coords_str, folds_str) = source.split_once(input, "\n\n", "blank line followed by folds")?;
 
     let dots = coords_str
         .lines()
-        .map(|x| Coords::from_str(x).unwrap())
-        .collect::<Vec<Coords>>();
+        .map(|x| Coords::parse(&source, x))
+        .collect::<Result<Vec<Coords>, ParseError>>()?;
 
     let folds = folds_str
         .lines()
-        .map(|x| Fold::from_str(x).unwrap())
-        .collect::<Vec<Fold>>();
+        .map(|x| Fold::parse(&source, x))
+        .collect::<Result<Vec<Fold>, ParseError>>()?;
+
+    if dots.is_empty() {
+        return Err(source.error(coords_str, "dot coordinates"));
+    }
+    if folds.is_empty() {
+        return Err(source.error(folds_str, "fold instruction"));
+    }
 
-    Input { dots, folds }
+    Ok(Input { dots, folds })
 }
 
 pub fn fold(dots: &[Coords], folds: &[Fold]) -> HashSet::<Coords> {
@@ -87,23 +131,23 @@ pub fn fold(dots: &[Coords], folds: &[Fold]) -> HashSet::<Coords> {
     HashSet::<Coords>::from_iter(folded_dots)
 }
 
-pub fn format_dots(coords: &HashSet<Coords>) -> String {
-    let max_x: usize = coords.iter().map(|c| c.x).max().unwrap();
-    let max_y: usize = coords.iter().map(|c| c.y).max().unwrap();
+/// Renders the dots on a paper reaching from the origin to the farthest dot,
+/// or returns `None` if that paper has more than `MAX_PAPER_CELLS` cells
+pub fn format_dots(coords: &HashSet<Coords>) -> Option<String> {
+    let max_x: usize = coords.iter().map(|c| c.x).max().unwrap_or(0);
+    let max_y: usize = coords.iter().map(|c| c.y).max().unwrap_or(0);
 
-    let mut out = String::new();
-    for y in 0..max_y+1 {
-        for x in 0..max_x+1 {
-            if coords.contains(&Coords { x, y }) {
-                out.push('#');
-            } else {
-                out.push('.');
-            }
-        }
-        out.push('\n');
+    let (width, height) = (max_x.checked_add(1)?, max_y.checked_add(1)?);
+    if width.checked_mul(height)? > MAX_PAPER_CELLS {
+        return None;
     }
 
-    out
+    let mut paper = Grid::new(width, height, false);
+    for c in coords {
+        paper[(c.x, c.y)] = true;
+    }
+
+    Some(paper.render(|dot| if *dot { '#' } else { '.' }))
 }
 
 #[aoc(day13, part1)]
@@ -113,14 +157,36 @@ pub fn solve_part1(input: &Input) -> usize {
 }
 
 #[aoc(day13, part2)]
-pub fn solve_part2(input: &Input) -> String {
+pub fn solve_part2(input: &Input) -> Result<String, SolveError> {
     let folded_dots = fold(&input.dots, &input.folds);
-    format_dots(&folded_dots)
+    format_dots(&folded_dots).ok_or_else(|| SolveError::new(DAY, "folded paper is too large to render"))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = DAY;
+    type Input = Input;
+    type Output = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input).to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     pub fn sample1() -> String {
         String::from("6,10
@@ -148,7 +214,7 @@ fold along x=5")
 
     #[test]
     pub fn test_input_generator_sample1() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
 
         assert_eq!(input.dots[0].x, 6);
         assert_eq!(input.dots[0].y, 10);
@@ -159,10 +225,10 @@ fold along x=5")
 
     #[test]
     pub fn test_format_coords() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
         let dots = HashSet::from_iter(input.dots.clone());
 
-        let printed = format_dots(&dots);
+        let printed = format_dots(&dots).unwrap();
 
         assert_eq!(printed, "...#..#..#.
 ....#......
@@ -183,26 +249,50 @@ fold along x=5")
     }
 
     #[test]
-    pub fn test_solve_part1() {
-        let input = input_generator(&sample1().as_str());
+    pub fn test_format_dots_too_large() {
+        let far = |x, y| HashSet::from([Coords { x: 0, y: 0 }, Coords { x, y }]);
 
-        let dots = solve_part1(&input);
+        assert_eq!(format_dots(&far(usize::MAX, 0)), None);
+        assert_eq!(format_dots(&far(2000, 2000)), None);
+        assert_eq!(format_dots(&far(1023, 1023)).map(|paper| paper.len()), Some(1024 * 1025));
 
-        assert_eq!(dots, 17);
+        let input = input_generator("0,0\n5000,5000\n\nfold along x=6000").unwrap();
+        assert_eq!(solve_part2(&input).unwrap_err().to_string(), "day 13: folded paper is too large to render");
     }
 
     #[test]
-    pub fn test_solve_part2() {
-        let input = input_generator(&sample1().as_str());
+    pub fn test_input_generator_unknown_fold() {
+        let error = input_generator("6,10\n0,14\n\nfold along z=7").unwrap_err();
 
-        let printed_dots = solve_part2(&input);
+        assert_eq!(error.to_string(),
+            "day 13, line 4, column 1: expected fold along x or fold along y, found \"fold along z\"");
+    }
 
-        println!("{}", printed_dots);
-        assert_eq!(printed_dots, "#####
-#...#
-#...#
-#...#
-#####
-");
+    fn fold_instruction() -> impl Strategy<Value = Fold> {
+        prop_oneof![any::<usize>().prop_map(Fold::X), any::<usize>().prop_map(Fold::Y)]
+    }
+
+    proptest! {
+        #[test]
+        fn test_fold_roundtrip(fold in fold_instruction()) {
+            let formatted = fold.to_string();
+
+            prop_assert_eq!(Fold::from_str(&formatted).unwrap().to_string(), formatted);
+        }
+
+        #[test]
+        fn test_coords_roundtrip(x in any::<usize>(), y in any::<usize>()) {
+            let formatted = Coords { x, y }.to_string();
+
+            prop_assert_eq!(Coords::from_str(&formatted).unwrap().to_string(), formatted);
+        }
+
+        #[test]
+        fn test_fold_and_coords_never_panic(bytes in prop::collection::vec(any::<u8>(), 0..32)) {
+            let s = String::from_utf8_lossy(&bytes);
+
+            let _ = Fold::from_str(&s);
+            let _ = Coords::from_str(&s);
+        }
     }
 }
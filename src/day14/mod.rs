@@ -1,30 +1,60 @@
 use std::collections::HashMap;
+use std::fmt;
 use itertools::Itertools;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
 
+const DAY: u8 = 14;
+
+#[derive(Debug)]
 pub struct Input {
     start: String,
     rules: HashMap<(char, char), char>
 }
 
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}\n", self.start)?;
+        for ((l, r), insert) in self.rules.iter().sorted() {
+            writeln!(f, "{}{} -> {}", l, r, insert)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_rule<'a>(source: &Source<'a>, line: &'a str) -> Result<((char, char), char), ParseError> {
+    let (l, r) = source.split_once(line, " -> ", "' -> ' followed by element")?;
+
+    let pair = match l.chars().collect_tuple::<(_, _)>() {
+        Some(pair) => pair,
+        None => return Err(source.error(l, "pair of elements")),
+    };
+    let insert = match r.chars().collect_tuple::<(_,)>() {
+        Some((insert,)) => insert,
+        None => return Err(source.error(r, "single element")),
+    };
+
+    Ok((pair, insert))
+}
+
 #[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> Input {
-    let (start, rules_str) = input.split_once("\n\n").unwrap();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let (start, rules_str) = source.split_once(input, "\n\n", "blank line followed by rules")?;
+
+    if start.is_empty() {
+        return Err(source.error(start, "polymer template"));
+    }
 
-    let rules_map: HashMap<_, _> = rules_str
+    let rules_map = rules_str
         .lines()
-        .map(|line| line
-            .split_once(" -> ")
-            .unwrap()
-            )
-        .map(|(l, r)|
-            (l.chars().next_tuple::<(_, _)>().unwrap(),
-            r.chars().next().unwrap()))
-        .collect();
-
-    Input {
+        .map(|line| parse_rule(&source, line))
+        .collect::<Result<HashMap<_, _>, ParseError>>()?;
+
+    Ok(Input {
         start: start.to_string(),
         rules: rules_map,
-    }
+    })
 }
 
 fn apply_rules(s: &str, rules: &HashMap<(char, char), char>, iterations: usize) -> u64 {
@@ -79,9 +109,31 @@ pub fn solve_part2(input: &Input) -> u64 {
     apply_rules(&input.start, &input.rules, 40)
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = DAY;
+    type Input = Input;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::needless_borrow, clippy::unnecessary_to_owned)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     pub fn sample1() -> String {
         String::from("NNCB
@@ -106,7 +158,7 @@ CN -> C")
 
     #[test]
     pub fn test_input_generator_sample1() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
 
         assert_eq!(input.start, "NNCB");
         assert_eq!(input.rules.get(&('C', 'B')).unwrap(), &'H');
@@ -114,7 +166,7 @@ CN -> C")
 
     #[test]
     pub fn test_apply_rules_once() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
 
         let new_word = apply_rules(&"NNCB".to_string(), &input.rules, 1);
 
@@ -124,7 +176,7 @@ CN -> C")
 
     #[test]
     pub fn test_apply_rules_twice() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
 
         let new_word = apply_rules(&"NNCB".to_string(), &input.rules, 2);
 
@@ -134,7 +186,7 @@ CN -> C")
 
     #[test]
     pub fn test_apply_rules_three_times() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
 
         let new_word = apply_rules(&"NNCB".to_string(), &input.rules, 3);
 
@@ -142,21 +194,18 @@ CN -> C")
         assert_eq!(new_word, 7);
     }
 
-    #[test]
-    pub fn test_solve_part1() {
-        let input = input_generator(&sample1().as_str());
-
-        let result = solve_part1(&input);
-
-        assert_eq!(result, 1588);
+    fn input() -> impl Strategy<Value = Input> {
+        let element = prop::char::range('A', 'Z');
+        ("[A-Z]{1,20}", prop::collection::hash_map((element.clone(), element.clone()), element, 0..30))
+            .prop_map(|(start, rules)| Input { start, rules })
     }
 
-    #[test]
-    pub fn test_solve_part2() {
-        let input = input_generator(&sample1().as_str());
-
-        let result = solve_part2(&input);
+    proptest! {
+        #[test]
+        fn test_input_generator_roundtrip(input in input()) {
+            let formatted = input.to_string();
 
-        assert_eq!(result, 2188189693529);
+            prop_assert_eq!(input_generator(&formatted).unwrap().to_string(), formatted);
+        }
     }
 }
@@ -1,78 +1,34 @@
-use std::collections::HashSet;
-use priority_queue::DoublePriorityQueue;
+use crate::error::{ParseError, SolveError, Source};
+use crate::graph::{self, Path};
+use crate::grid::Grid;
+use crate::solution::Solution;
 
-pub struct Input {
-    map: Vec<Vec<u8>>,
-}
-
-impl Input {
-    fn get_risk_to(&self, index: usize) -> u8 {
-        let (x, y) = self.index_to_xy(index);
-        self.map[y][x]
-    }
-
-    fn index_to_xy(&self, index: usize) -> (usize, usize) {
-        let edge_len = self.map.len();
-        let y = (index - index % edge_len) / edge_len;
-        let x = index % edge_len;
-        (x, y)
-    }
-
-    fn xy_to_index(&self, x: usize, y: usize) -> usize {
-        let edge_len = self.map.len();
-        y * edge_len + x
-    }
-
-    fn neighbors(&self, index: usize) -> HashSet<usize> {
-        let (x, y) = self.index_to_xy(index);
-        let edge_len = self.map.len();
-
-        let mut neighbors = HashSet::new();
-        if y > 0 {
-            neighbors.insert(self.xy_to_index(x, y - 1));
-        }
-        if x > 0 {
-            neighbors.insert(self.xy_to_index(x - 1, y));
-        }
-        if y < edge_len {
-            neighbors.insert(self.xy_to_index(x, y + 1));
-        }
-        if x < edge_len {
-            neighbors.insert(self.xy_to_index(x + 1, y));
-        }
+const DAY: u8 = 15;
 
-        neighbors
-    }
+#[derive(Debug)]
+pub struct Input {
+    map: Grid<u8>,
 }
 
 #[aoc_generator(day15)]
-pub fn input_generator(input: &str) -> Input {
-    let map = input
-        .lines()
-        .map(|line| line
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as u8)
-            .collect::<Vec<u8>>()
-            )
-        .collect::<Vec<Vec<u8>>>();
-
-    Input { map }
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let map = Grid::parse_digits(&Source::new(DAY, input), input, "risk level digit")?;
+
+    Ok(Input { map })
 }
 
 fn quintuple_input(input: &Input) -> Input {
-    let tile_edge_len = input.map.len();
-    let new_edge_len = tile_edge_len * 5;
-    let mut new_map = vec![vec![0u8; new_edge_len]; new_edge_len];
+    let tile_width = input.map.width();
+    let tile_height = input.map.height();
+    let mut new_map = Grid::new(tile_width * 5, tile_height * 5, 0u8);
 
     for tile_y in 0..5 {
         for tile_x in 0..5 {
-            for (y, line) in input.map.iter().enumerate() {
-                for (x, value) in line.iter().enumerate() {
-                    let tiled_x = x + tile_x * tile_edge_len;
-                    let tiled_y = y + tile_y * tile_edge_len;
-                    let new_value = ((*value as usize + tile_y + tile_x - 1) % 9) as u8 + 1;
-                    new_map[tiled_y][tiled_x] = new_value;
-                }
+            for ((x, y), value) in input.map.iter() {
+                let tiled_x = x + tile_x * tile_width;
+                let tiled_y = y + tile_y * tile_height;
+                let new_value = ((*value as usize + tile_y + tile_x - 1) % 9) as u8 + 1;
+                new_map[(tiled_x, tiled_y)] = new_value;
             }
         }
     }
@@ -81,55 +37,61 @@ fn quintuple_input(input: &Input) -> Input {
 }
 
 
-fn dijkstra(input: &Input) -> usize {
-    let map_edge_len = input.map.len();
-    let num_nodes = map_edge_len.pow(2);
-    let mut dist = vec![usize::MAX; num_nodes];
-    let mut prev: Vec<Option<usize>> = vec![None; num_nodes];
-
-    let start = 0;
-    let target = num_nodes - 1;
-    dist[start] = 0;
-
-    let mut queue = DoublePriorityQueue::<usize, usize>::from_iter(
-        (0..map_edge_len.pow(2)).map(|v| (v, dist[v])));
-
-    while !queue.is_empty() {
-        let u = *queue.peek_min().unwrap().0;
-        queue.remove(&u);
-
-        if u == target {
-            return dist[u];
-        }
+/// Path of lowest total risk from the top left to the bottom right corner
+pub fn lowest_risk_path(input: &Input) -> Option<Path<(usize, usize), usize>> {
+    let map = &input.map;
+    let target = (map.width() - 1, map.height() - 1);
 
-        for v in input.neighbors(u) {
-            if queue.get(&v).is_some() {
-                let alt = dist[u] + input.get_risk_to(v) as usize;
-                if alt < dist[v] {
-                    dist[v] = alt;
-                    prev[v] = Some(u);
-                    queue.change_priority(&v, alt);
-                }
-            }
-        }
-    }
+    graph::dijkstra(
+        (0, 0),
+        |(x, y)| map
+            .neighbors4(*x, *y)
+            .map(|n| (n, map[n] as usize))
+            .collect::<Vec<_>>(),
+        |node| *node == target)
+}
 
-    usize::MAX
+fn lowest_total_risk(input: &Input) -> usize {
+    lowest_risk_path(input)
+        .expect("All cells of a grid are connected")
+        .cost
 }
 
 #[aoc(day15, part1)]
 pub fn solve_part1(input: &Input) -> usize {
-    dijkstra(input)
+    lowest_total_risk(input)
 }
 
 #[aoc(day15, part2)]
 pub fn solve_part2(input: &Input) -> usize {
-    dijkstra(&quintuple_input(input))
+    lowest_total_risk(&quintuple_input(input))
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = DAY;
+    type Input = Input;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod test {
     use super::*;
+    use std::collections::HashSet;
 
     pub fn sample1() -> String {
         String::from("1163751742
@@ -146,47 +108,40 @@ mod test {
 
     #[test]
     pub fn test_input_generator_sample1() {
-        let input = input_generator(&sample1().as_str());
+        let input = input_generator(&sample1().as_str()).unwrap();
 
-        assert_eq!(input.map[1][2], 8);
+        assert_eq!(input.map[(2, 1)], 8);
     }
 
     #[test]
     pub fn test_calc_neighbors() {
-        let input = input_generator(&sample1().as_str());
-
-        assert_eq!(input.neighbors(0), HashSet::from_iter([1, 10]));
-        assert_eq!(input.neighbors(11), HashSet::from_iter([10, 12, 1, 21]));
+        let input = input_generator(&sample1().as_str()).unwrap();
+
+        assert_eq!(HashSet::<_>::from_iter(input.map.neighbors4(0, 0)),
+            HashSet::from_iter([(1, 0), (0, 1)]));
+        assert_eq!(HashSet::<_>::from_iter(input.map.neighbors4(1, 1)),
+            HashSet::from_iter([(0, 1), (2, 1), (1, 0), (1, 2)]));
+        assert_eq!(HashSet::<_>::from_iter(input.map.neighbors4(9, 9)),
+            HashSet::from_iter([(8, 9), (9, 8)]));
     }
 
-
     #[test]
     pub fn test_quintuple_input() {
-        let input = input_generator("12\n34");
+        let input = input_generator("12\n34").unwrap();
 
         let quintupled_input = quintuple_input(&input);
 
-        assert_eq!(quintupled_input.map[0], vec![1, 2, 2, 3, 3, 4, 4, 5, 5, 6]);
-        assert_eq!(quintupled_input.map[1], vec![3, 4, 4, 5, 5, 6, 6, 7, 7, 8]);
+        assert_eq!(quintupled_input.map.row(0), [1, 2, 2, 3, 3, 4, 4, 5, 5, 6]);
+        assert_eq!(quintupled_input.map.row(1), [3, 4, 4, 5, 5, 6, 6, 7, 7, 8]);
     }
 
     #[test]
-    pub fn test_solve_part1() {
-        let input = input_generator(&sample1().as_str());
+    pub fn test_lowest_risk_path() {
+        let input = input_generator("19\n11").unwrap();
 
-        let total_cost = solve_part1(&input);
+        let path = lowest_risk_path(&input).unwrap();
 
-        assert_eq!(total_cost, 40);
+        assert_eq!(path.nodes, vec![(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(path.cost, 2);
     }
-
-    #[test]
-    pub fn test_solve_part2() {
-        let input = input_generator(&sample1().as_str());
-
-        let total_cost = solve_part2(&input);
-
-        assert_eq!(total_cost, 315);
-    }
-
 }
-
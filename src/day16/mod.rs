@@ -0,0 +1,381 @@
+use std::fmt;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 16;
+const LITERAL_TYPE: u64 = 4;
+/// Deepest nesting of sub-packets, which keeps the recursion over packets from
+/// overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+/// How an operator packet announces the extent of its sub-packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    /// Length type ID 0: total number of bits of all sub-packets
+    Bits(usize),
+    /// Length type ID 1: number of immediate sub-packets
+    Packets(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    Literal(u64),
+    Operator { operator: Operator, length: Length, packets: Vec<Packet> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub payload: Payload,
+}
+
+impl Operator {
+    fn from_type_id(type_id: u64) -> Option<Operator> {
+        match type_id {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Minimum),
+            3 => Some(Operator::Maximum),
+            5 => Some(Operator::GreaterThan),
+            6 => Some(Operator::LessThan),
+            7 => Some(Operator::EqualTo),
+            _ => None,
+        }
+    }
+
+    fn is_comparison(&self) -> bool {
+        matches!(self, Operator::GreaterThan | Operator::LessThan | Operator::EqualTo)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "minimum",
+            Operator::Maximum => "maximum",
+            Operator::GreaterThan => "greater than",
+            Operator::LessThan => "less than",
+            Operator::EqualTo => "equal to",
+        }
+    }
+}
+
+impl Packet {
+    /// Sum of the versions of this packet and all packets nested in it
+    pub fn version_sum(&self) -> u64 {
+        let nested = match &self.payload {
+            Payload::Literal(_) => 0,
+            Payload::Operator { packets, .. } => packets.iter().map(Packet::version_sum).sum(),
+        };
+        self.version as u64 + nested
+    }
+
+    /// Value of the expression, or `None` if a sum or product along the way
+    /// does not fit in a u64
+    pub fn evaluate(&self) -> Option<u64> {
+        let (operator, packets) = match &self.payload {
+            Payload::Literal(value) => return Some(*value),
+            Payload::Operator { operator, packets, .. } => (operator, packets),
+        };
+
+        let values = packets.iter().map(Packet::evaluate).collect::<Option<Vec<u64>>>()?;
+        let value = match operator {
+            Operator::Sum => values.iter().try_fold(0u64, |sum, value| sum.checked_add(*value))?,
+            Operator::Product => values.iter().try_fold(1u64, |product, value| product.checked_mul(*value))?,
+            Operator::Minimum => *values.iter().min().unwrap(),
+            Operator::Maximum => *values.iter().max().unwrap(),
+            Operator::GreaterThan => (values[0] > values[1]) as u64,
+            Operator::LessThan => (values[0] < values[1]) as u64,
+            Operator::EqualTo => (values[0] == values[1]) as u64,
+        };
+        Some(value)
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}v{} ", "", self.version, indent = 2 * depth)?;
+        match &self.payload {
+            Payload::Literal(value) => writeln!(f, "literal {}", value),
+            Payload::Operator { operator, length, packets } => {
+                match length {
+                    Length::Bits(bits) => writeln!(f, "{} ({} bits)", operator.name(), bits)?,
+                    Length::Packets(count) => writeln!(f, "{} ({} packets)", operator.name(), count)?,
+                }
+                packets.iter().try_for_each(|packet| packet.write_indented(f, depth + 1))
+            },
+        }
+    }
+}
+
+/// Pretty-prints the packet as a tree with one packet per line, indenting
+/// sub-packets below their operator
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// Decoding error at a bit offset into the transmission
+#[derive(Debug)]
+struct DecodeError {
+    bit: usize,
+    expected: &'static str,
+}
+
+struct BitReader {
+    bits: Vec<bool>,
+    pos: usize,
+}
+
+impl BitReader {
+    fn read(&mut self, count: usize, expected: &'static str) -> Result<u64, DecodeError> {
+        if self.pos + count > self.bits.len() {
+            return Err(DecodeError { bit: self.bits.len(), expected });
+        }
+
+        let value = self.bits[self.pos..self.pos + count]
+            .iter()
+            .fold(0, |value, bit| (value << 1) | *bit as u64);
+        self.pos += count;
+        Ok(value)
+    }
+
+    fn read_literal(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        loop {
+            let start = self.pos;
+            let group = self.read(5, "literal group")?;
+            if value.leading_zeros() < 4 {
+                return Err(DecodeError { bit: start, expected: "literal of at most 64 bits" });
+            }
+            value = (value << 4) | (group & 0xf);
+            if group & 0x10 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn read_packet(&mut self, depth: usize) -> Result<Packet, DecodeError> {
+        if depth > MAX_DEPTH {
+            return Err(DecodeError { bit: self.pos, expected: "sub-packets nested at most 64 deep" });
+        }
+
+        let version = self.read(3, "packet version")? as u8;
+        let type_start = self.pos;
+        let type_id = self.read(3, "packet type ID")?;
+
+        if type_id == LITERAL_TYPE {
+            return Ok(Packet { version, payload: Payload::Literal(self.read_literal()?) });
+        }
+
+        let operator = Operator::from_type_id(type_id).unwrap();
+        let mut packets = Vec::new();
+        let length = if self.read(1, "length type ID")? == 0 {
+            let bits = self.read(15, "total length in bits")? as usize;
+            let end = self.pos + bits;
+            while self.pos < end {
+                packets.push(self.read_packet(depth + 1)?);
+            }
+            if self.pos > end {
+                return Err(DecodeError { bit: end, expected: "sub-packets within total length" });
+            }
+            Length::Bits(bits)
+        } else {
+            let count = self.read(11, "number of sub-packets")? as usize;
+            for _ in 0..count {
+                packets.push(self.read_packet(depth + 1)?);
+            }
+            Length::Packets(count)
+        };
+
+        if packets.is_empty() {
+            return Err(DecodeError { bit: self.pos, expected: "sub-packet" });
+        }
+        if operator.is_comparison() && packets.len() != 2 {
+            return Err(DecodeError { bit: type_start, expected: "comparison of exactly two sub-packets" });
+        }
+
+        Ok(Packet { version, payload: Payload::Operator { operator, length, packets } })
+    }
+}
+
+#[aoc_generator(day16)]
+pub fn input_generator(input: &str) -> Result<Packet, ParseError> {
+    let source = Source::new(DAY, input);
+    let hex = input.trim_end();
+
+    let mut bits = Vec::with_capacity(4 * hex.len());
+    for (pos, c) in hex.char_indices() {
+        let nibble = c
+            .to_digit(16)
+            .ok_or_else(|| source.error(&hex[pos..pos + c.len_utf8()], "hexadecimal digit"))?;
+        bits.extend((0..4).rev().map(|shift| (nibble >> shift) & 1 == 1));
+    }
+
+    let mut reader = BitReader { bits, pos: 0 };
+    reader.read_packet(0).map_err(|e| {
+        // Point at the hex digit holding the offending bit
+        let pos = e.bit / 4;
+        source.error(&hex[pos..(pos + 1).min(hex.len())], e.expected)
+    })
+}
+
+#[aoc(day16, part1)]
+pub fn solve_part1(packet: &Packet) -> u64 {
+    packet.version_sum()
+}
+
+#[aoc(day16, part2)]
+pub fn solve_part2(packet: &Packet) -> Result<u64, SolveError> {
+    packet.evaluate().ok_or_else(|| SolveError::new(DAY, "value does not fit in 64 bits"))
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = DAY;
+    type Input = Packet;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn literal(version: u8, value: u64) -> Packet {
+        Packet { version, payload: Payload::Literal(value) }
+    }
+
+    #[test]
+    pub fn test_literal() {
+        assert_eq!(input_generator("D2FE28").unwrap(), literal(6, 2021));
+    }
+
+    #[test]
+    pub fn test_operator_total_length() {
+        let packet = input_generator("38006F45291200").unwrap();
+
+        assert_eq!(packet, Packet {
+            version: 1,
+            payload: Payload::Operator {
+                operator: Operator::LessThan,
+                length: Length::Bits(27),
+                packets: vec![literal(6, 10), literal(2, 20)],
+            },
+        });
+    }
+
+    #[test]
+    pub fn test_operator_packet_count() {
+        let packet = input_generator("EE00D40C823060").unwrap();
+
+        assert_eq!(packet, Packet {
+            version: 7,
+            payload: Payload::Operator {
+                operator: Operator::Maximum,
+                length: Length::Packets(3),
+                packets: vec![literal(2, 1), literal(4, 2), literal(1, 3)],
+            },
+        });
+    }
+
+    #[test]
+    pub fn test_version_sum() {
+        let sums = ["8A004A801A8002F478", "620080001611562C8802118E34", "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780"]
+            .map(|hex| input_generator(hex).unwrap().version_sum());
+
+        assert_eq!(sums, [16, 12, 23, 31]);
+    }
+
+    #[test]
+    pub fn test_evaluate() {
+        let values = ["C200B40A82", "04005AC33890", "880086C3E88112", "CE00C43D881120", "D8005AC2A8F0",
+            "F600BC2D8F", "9C005AC2F8F0", "9C0141080250320F1802104A08"]
+            .map(|hex| input_generator(hex).unwrap().evaluate());
+
+        assert_eq!(values, [3, 54, 7, 9, 1, 0, 0, 1].map(Some));
+    }
+
+    #[test]
+    pub fn test_evaluate_overflow() {
+        let operator = |operator, packets: Vec<Packet>| {
+            let length = Length::Packets(packets.len());
+            Packet { version: 0, payload: Payload::Operator { operator, length, packets } }
+        };
+        let sum = operator(Operator::Sum, vec![literal(0, u64::MAX), literal(0, 1)]);
+        let product = operator(Operator::Product, vec![literal(0, 1 << 32), literal(0, 1 << 32)]);
+        let minimum = operator(Operator::Minimum, vec![literal(0, 0), product.clone()]);
+
+        assert_eq!(sum.evaluate(), None);
+        assert_eq!(product.evaluate(), None);
+        assert_eq!(minimum.evaluate(), None);
+        assert_eq!(solve_part2(&sum).unwrap_err().to_string(), "day 16: value does not fit in 64 bits");
+    }
+
+    #[test]
+    pub fn test_pretty_print() {
+        let packet = input_generator("9C0141080250320F1802104A08").unwrap();
+
+        assert_eq!(packet.to_string(), "v4 equal to (80 bits)
+  v2 sum (2 packets)
+    v2 literal 1
+    v4 literal 3
+  v6 product (2 packets)
+    v0 literal 2
+    v2 literal 2
+");
+    }
+
+    #[test]
+    pub fn test_input_generator_errors() {
+        let error = input_generator("D2FG28").unwrap_err();
+        assert_eq!((error.column, error.token.as_str(), error.expected), (4, "G", "hexadecimal digit"));
+
+        let error = input_generator("D2FE").unwrap_err();
+        assert_eq!((error.column, error.token.as_str(), error.expected), (5, "", "literal group"));
+    }
+
+    // Transmission of `depth` sums of a single sub-packet each, around the literal 1
+    fn nested(depth: usize) -> String {
+        let mut bits = "000000100000000001".repeat(depth) + "00010000001";
+        while !bits.len().is_multiple_of(4) {
+            bits.push('0');
+        }
+        bits.as_bytes()
+            .chunks(4)
+            .map(|nibble| format!("{:X}", u8::from_str_radix(std::str::from_utf8(nibble).unwrap(), 2).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_input_generator_nesting() {
+        assert_eq!(input_generator(&nested(MAX_DEPTH)).unwrap().evaluate(), Some(1));
+
+        let error = input_generator(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(error.expected, "sub-packets nested at most 64 deep");
+        assert!(input_generator(&nested(100_000)).is_err());
+    }
+}
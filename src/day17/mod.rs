@@ -0,0 +1,268 @@
+use std::ops::RangeInclusive;
+use crate::error::{ParseError, SolveError, Source};
+use crate::geometry::{self, Point};
+use crate::solution::Solution;
+
+const DAY: u8 = 17;
+/// Largest distance of the target from the launcher along either axis, which
+/// keeps every position the search simulates well within an i64
+const MAX_DISTANCE: i64 = 1 << 31;
+
+/// Target area, which has to lie to the right of and below the launcher at (0, 0),
+/// at most `MAX_DISTANCE` away from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub x: RangeInclusive<i64>,
+    pub y: RangeInclusive<i64>,
+}
+
+/// Steps after launch during which one coordinate of the probe lies within
+/// the target, where `last` is `None` if it stays there forever
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Steps {
+    first: usize,
+    last: Option<usize>,
+}
+
+impl Steps {
+    fn overlaps(&self, other: &Steps) -> bool {
+        self.last.is_none_or(|last| last >= other.first) && other.last.is_none_or(|last| last >= self.first)
+    }
+}
+
+#[aoc_generator(day17)]
+pub fn input_generator(input: &str) -> Result<Target, ParseError> {
+    let source = Source::new(DAY, input);
+    let ranges_str = input.strip_prefix("target area: ").ok_or_else(|| source.error(input, "target area: "))?;
+    let (x_str, y_str) = source.split_once(ranges_str, ", ", "', ' followed by y range")?;
+
+    let x = geometry::parse_range(&source, x_str, "x=")?;
+    let y = geometry::parse_range(&source, y_str, "y=")?;
+
+    if *x.start() <= 0 {
+        return Err(source.error(x_str, "x range right of the launcher"));
+    }
+    if *y.end() >= 0 {
+        return Err(source.error(y_str, "y range below the launcher"));
+    }
+    if *x.end() > MAX_DISTANCE {
+        return Err(source.error(x_str, "x range up to 2147483648"));
+    }
+    if *y.start() < -MAX_DISTANCE {
+        return Err(source.error(y_str, "y range down to -2147483648"));
+    }
+
+    Ok(Target { x, y })
+}
+
+/// Endless sequence of probe positions for an initial velocity, starting at the launcher
+pub fn trajectory(velocity: Point) -> impl Iterator<Item = Point> {
+    let origin = Point::new(0, 0);
+    std::iter::successors(Some((origin, velocity)), |(position, velocity)| {
+        let next = Point::new(position.x + velocity.x, position.y + velocity.y);
+        Some((next, Point::new(velocity.x - velocity.x.signum(), velocity.y - 1)))
+    })
+    .map(|(position, _)| position)
+}
+
+/// Highest position reached when launching with an upwards speed of `vy`, or
+/// `None` if it does not fit in an i64
+pub fn apex(vy: i64) -> Option<i64> {
+    if vy <= 0 {
+        return Some(0);
+    }
+
+    // Halving whichever factor is even keeps the product exact
+    let next = vy.checked_add(1)?;
+    if vy % 2 == 0 { (vy / 2).checked_mul(next) } else { vy.checked_mul(next / 2) }
+}
+
+impl Target {
+    pub fn contains(&self, point: Point) -> bool {
+        self.x.contains(&point.x) && self.y.contains(&point.y)
+    }
+
+    /// Trajectory up to its first position within the target, or up to the
+    /// first position from which the target cannot be reached anymore
+    pub fn flight(&self, velocity: Point) -> Vec<Point> {
+        let mut points = Vec::new();
+        for point in trajectory(velocity) {
+            points.push(point);
+            if self.contains(point) || point.x > *self.x.end() || point.y < *self.y.start() {
+                break;
+            }
+        }
+        points
+    }
+
+    fn x_steps(&self, vx: i64) -> Option<Steps> {
+        let (mut x, mut v, mut step) = (0, vx, 0);
+        let mut first = None;
+
+        while x <= *self.x.end() && (v > 0 || self.x.contains(&x)) {
+            if self.x.contains(&x) {
+                first = first.or(Some(step));
+                if v == 0 {
+                    return first.map(|first| Steps { first, last: None });
+                }
+            }
+            x += v;
+            v -= 1;
+            step += 1;
+        }
+
+        first.map(|first| Steps { first, last: Some(step - 1) })
+    }
+
+    fn y_steps(&self, vy: i64) -> Option<Steps> {
+        let (mut y, mut v, mut step) = (0, vy, 0);
+        let mut steps: Option<Steps> = None;
+
+        while y >= *self.y.start() {
+            if self.y.contains(&y) {
+                steps = Some(Steps { first: steps.map_or(step, |s| s.first), last: Some(step) });
+            }
+            y += v;
+            v -= 1;
+            step += 1;
+        }
+
+        steps
+    }
+
+    /// All initial velocities that put the probe within the target after some step.
+    ///
+    /// Speeds beyond the target's far edge overshoot it right away, and since
+    /// a probe launched upwards with `vy` passes y = 0 again with `-vy - 1`,
+    /// any `vy` above `-y.start() - 1` skips the target as well. Within these
+    /// bounds, a velocity hits if the steps spent within the target's x and y
+    /// ranges overlap.
+    pub fn hitting_velocities(&self) -> Vec<Point> {
+        let x_steps = (1..=*self.x.end())
+            .filter_map(|vx| self.x_steps(vx).map(|steps| (vx, steps)))
+            .collect::<Vec<(i64, Steps)>>();
+
+        let mut velocities = Vec::new();
+        for vy in *self.y.start()..=-(*self.y.start() + 1) {
+            if let Some(y_steps) = self.y_steps(vy) {
+                velocities.extend(x_steps
+                    .iter()
+                    .filter(|(_, steps)| steps.overlaps(&y_steps))
+                    .map(|(vx, _)| Point::new(*vx, vy)));
+            }
+        }
+        velocities
+    }
+}
+
+#[aoc(day17, part1)]
+pub fn solve_part1(target: &Target) -> Result<i64, SolveError> {
+    let highest = target
+        .hitting_velocities()
+        .iter()
+        .map(|velocity| velocity.y)
+        .max()
+        .ok_or_else(|| SolveError::new(DAY, "no velocity hits the target"))?;
+
+    apex(highest).ok_or_else(|| SolveError::new(DAY, "highest position does not fit in 64 bits"))
+}
+
+#[aoc(day17, part2)]
+pub fn solve_part2(target: &Target) -> i64 {
+    target.hitting_velocities().len() as i64
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = DAY;
+    type Input = Target;
+    type Output = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Ok(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub fn sample_str() -> String {
+        String::from("target area: x=20..30, y=-10..-5")
+    }
+
+    #[test]
+    pub fn test_input_generator() {
+        let target = input_generator(sample_str().as_str()).unwrap();
+
+        assert_eq!(target, Target { x: 20..=30, y: -10..=-5 });
+    }
+
+    #[test]
+    pub fn test_input_generator_invalid() {
+        let error = input_generator("target area: x=20..30, y=-10.-5").unwrap_err();
+        assert_eq!((error.column, error.expected), (32, "'..' followed by range end"));
+
+        let error = input_generator("target area: x=20..30, y=5..10").unwrap_err();
+        assert_eq!((error.column, error.expected), (24, "y range below the launcher"));
+
+        let error = input_generator("target area: x=1..2, y=-9223372036854775808..-1").unwrap_err();
+        assert_eq!((error.column, error.expected), (22, "y range down to -2147483648"));
+        let error = input_generator("target area: x=1..2147483649, y=-10..-5").unwrap_err();
+        assert_eq!((error.column, error.expected), (14, "x range up to 2147483648"));
+    }
+
+    #[test]
+    pub fn test_solve_part1_no_hit() {
+        // Only the parser rules out targets above the launcher
+        let target = Target { x: 20..=30, y: 5..=10 };
+
+        assert_eq!(solve_part1(&target).unwrap_err().to_string(), "day 17: no velocity hits the target");
+        assert_eq!(solve_part2(&target), 0);
+    }
+
+    #[test]
+    pub fn test_flight() {
+        let target = input_generator(sample_str().as_str()).unwrap();
+
+        assert_eq!(target.flight(Point::new(7, 2)).last(), Some(&Point::new(28, -7)));
+        assert_eq!(target.flight(Point::new(7, 2)).len(), 8);
+        assert_eq!(target.flight(Point::new(6, 3)).last(), Some(&Point::new(21, -9)));
+        assert!(!target.contains(*target.flight(Point::new(17, -4)).last().unwrap()));
+    }
+
+    #[test]
+    pub fn test_trajectory_apex() {
+        let highest = trajectory(Point::new(6, 9)).take(20).map(|p| p.y).max();
+
+        assert_eq!(highest, apex(9));
+        assert_eq!(apex(9), Some(45));
+        assert_eq!(apex(i64::MAX), None);
+        assert_eq!(apex(4294967295), Some(9223372034707292160));
+    }
+
+    #[test]
+    pub fn test_hitting_velocities_match_simulation() {
+        let target = input_generator(sample_str().as_str()).unwrap();
+
+        let mut analytic = target.hitting_velocities();
+        let mut simulated = (0..=50)
+            .flat_map(|vx| (-50..=50).map(move |vy| Point::new(vx, vy)))
+            .filter(|velocity| target.contains(*target.flight(*velocity).last().unwrap()))
+            .collect::<Vec<Point>>();
+        analytic.sort();
+        simulated.sort();
+
+        assert_eq!(analytic, simulated);
+        assert!(analytic.contains(&Point::new(6, 9)));
+    }
+}
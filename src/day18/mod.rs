@@ -0,0 +1,382 @@
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 18;
+const EXPLODE_DEPTH: usize = 4;
+const SPLIT_THRESHOLD: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnailfishNumber {
+    Regular(u32),
+    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+}
+
+use SnailfishNumber::{Pair, Regular};
+
+// The first character of `s`, or an empty token at its end
+fn next_token(s: &str) -> &str {
+    &s[..s.chars().next().map_or(0, char::len_utf8)]
+}
+
+fn expect_char<'a>(source: &Source<'a>, s: &'a str, c: char, expected: &'static str)
+    -> Result<&'a str, ParseError> {
+    s.strip_prefix(c).ok_or_else(|| source.error(next_token(s), expected))
+}
+
+impl SnailfishNumber {
+    pub fn pair(left: SnailfishNumber, right: SnailfishNumber) -> SnailfishNumber {
+        Pair(Box::new(left), Box::new(right))
+    }
+
+    // Parses the element at the start of `s`, returning it along with the rest of `s`
+    fn parse_element<'a>(source: &Source<'a>, s: &'a str) -> Result<(SnailfishNumber, &'a str), ParseError> {
+        if let Some(rest) = s.strip_prefix('[') {
+            let (left, rest) = SnailfishNumber::parse_element(source, rest)?;
+            let rest = expect_char(source, rest, ',', "','")?;
+            let (right, rest) = SnailfishNumber::parse_element(source, rest)?;
+            let rest = expect_char(source, rest, ']', "']'")?;
+            return Ok((SnailfishNumber::pair(left, right), rest));
+        }
+
+        let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        if digits == 0 {
+            return Err(source.error(next_token(s), "'[' or regular number"));
+        }
+        let value = source.parse::<u32>(&s[..digits], "regular number")?;
+
+        Ok((Regular(value), &s[digits..]))
+    }
+
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<SnailfishNumber, ParseError> {
+        let (number, rest) = SnailfishNumber::parse_element(source, s)?;
+        if !rest.is_empty() {
+            return Err(source.error(rest, "end of line"));
+        }
+
+        Ok(number)
+    }
+
+    /// Magnitude of the number, or `None` if it does not fit in a u64
+    pub fn magnitude(&self) -> Option<u64> {
+        match self {
+            Regular(value) => Some(*value as u64),
+            Pair(left, right) => left.magnitude()?.checked_mul(3)?.checked_add(right.magnitude()?.checked_mul(2)?),
+        }
+    }
+
+    fn add_to_leftmost(&mut self, value: u32) -> Result<(), Overflow> {
+        match self {
+            Regular(v) => *v = v.checked_add(value).ok_or(Overflow)?,
+            Pair(left, _) => left.add_to_leftmost(value)?,
+        }
+        Ok(())
+    }
+
+    fn add_to_rightmost(&mut self, value: u32) -> Result<(), Overflow> {
+        match self {
+            Regular(v) => *v = v.checked_add(value).ok_or(Overflow)?,
+            Pair(_, right) => right.add_to_rightmost(value)?,
+        }
+        Ok(())
+    }
+
+    // Explodes the leftmost pair of regular numbers nested at least `EXPLODE_DEPTH`
+    // deep, returning the values still to be added to the left and to the right
+    fn explode_at(&mut self, depth: usize) -> Result<Option<Carry>, Overflow> {
+        let (left, right) = match self {
+            Regular(_) => return Ok(None),
+            Pair(left, right) => (left, right),
+        };
+
+        if depth >= EXPLODE_DEPTH {
+            if let (Regular(l), Regular(r)) = (&**left, &**right) {
+                let carry = (Some(*l), Some(*r));
+                *self = Regular(0);
+                return Ok(Some(carry));
+            }
+        }
+
+        if let Some((carry_left, carry_right)) = left.explode_at(depth + 1)? {
+            if let Some(value) = carry_right {
+                right.add_to_leftmost(value)?;
+            }
+            return Ok(Some((carry_left, None)));
+        }
+        if let Some((carry_left, carry_right)) = right.explode_at(depth + 1)? {
+            if let Some(value) = carry_left {
+                left.add_to_rightmost(value)?;
+            }
+            return Ok(Some((None, carry_right)));
+        }
+
+        Ok(None)
+    }
+
+    /// Performs a single explode action, if there is any pair to explode.
+    /// Returns `None` if a carried value does not fit in its neighbour, which
+    /// leaves the number partly exploded.
+    pub fn explode(&mut self) -> Option<bool> {
+        self.explode_at(0).map(|carry| carry.is_some()).ok()
+    }
+
+    /// Performs a single split action, if there is any regular number to split
+    pub fn split(&mut self) -> bool {
+        match self {
+            Regular(value) if *value >= SPLIT_THRESHOLD => {
+                *self = SnailfishNumber::pair(Regular(*value / 2), Regular(value.div_ceil(2)));
+                true
+            },
+            Regular(_) => false,
+            Pair(left, right) => left.split() || right.split(),
+        }
+    }
+
+    /// Applies explode and split actions until neither applies anymore, always
+    /// exploding first. Returns `None` if an explosion overflows.
+    pub fn reduce(&mut self) -> Option<()> {
+        while self.explode()? || self.split() {}
+        Some(())
+    }
+
+    /// Adds two numbers, or returns `None` if a regular number of the sum does
+    /// not fit in a u32 while reducing it
+    pub fn checked_add(self, other: SnailfishNumber) -> Option<SnailfishNumber> {
+        let mut sum = SnailfishNumber::pair(self, other);
+        sum.reduce()?;
+        Some(sum)
+    }
+}
+
+// Values of an exploded pair that are still to be added to the left and right
+type Carry = (Option<u32>, Option<u32>);
+
+// A carried value that does not fit in the regular number it is added to
+#[derive(Debug)]
+struct Overflow;
+
+impl FromStr for SnailfishNumber {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SnailfishNumber::parse(&Source::new(DAY, s), s)
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Regular(value) => write!(f, "{}", value),
+            Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+/// # Panics
+///
+/// Panics if a regular number does not fit in a u32 while reducing the sum,
+/// see `SnailfishNumber::checked_add`.
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, other: SnailfishNumber) -> SnailfishNumber {
+        self.checked_add(other).expect("Regular number overflowed while reducing")
+    }
+}
+
+fn overflow() -> SolveError {
+    SolveError::new(DAY, "regular number does not fit in 32 bits")
+}
+
+fn magnitude(number: &SnailfishNumber) -> Result<u64, SolveError> {
+    number.magnitude().ok_or_else(|| SolveError::new(DAY, "magnitude does not fit in 64 bits"))
+}
+
+/// Adds up all numbers from first to last
+pub fn sum(numbers: &[SnailfishNumber]) -> Result<SnailfishNumber, SolveError> {
+    let (first, rest) = numbers.split_first().ok_or_else(|| SolveError::new(DAY, "there are no numbers"))?;
+    rest.iter().try_fold(first.clone(), |sum, number| sum.checked_add(number.clone()).ok_or_else(overflow))
+}
+
+/// Largest magnitude of the sum of two different numbers, in either order
+pub fn max_pair_magnitude(numbers: &[SnailfishNumber]) -> Result<u64, SolveError> {
+    (0..numbers.len())
+        .flat_map(|i| (0..numbers.len()).filter(move |j| i != *j).map(move |j| (i, j)))
+        .map(|(i, j)| magnitude(&numbers[i].clone().checked_add(numbers[j].clone()).ok_or_else(overflow)?))
+        .try_fold(None, |max: Option<u64>, magnitude| Ok(max.max(Some(magnitude?))))?
+        .ok_or_else(|| SolveError::new(DAY, "there are fewer than two numbers"))
+}
+
+#[aoc_generator(day18)]
+pub fn input_generator(input: &str) -> Result<Vec<SnailfishNumber>, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|line| SnailfishNumber::parse(&source, line))
+        .collect::<Result<Vec<SnailfishNumber>, ParseError>>()
+}
+
+#[aoc(day18, part1)]
+pub fn solve_part1(numbers: &[SnailfishNumber]) -> Result<u64, SolveError> {
+    magnitude(&sum(numbers)?)
+}
+
+#[aoc(day18, part2)]
+pub fn solve_part2(numbers: &[SnailfishNumber]) -> Result<u64, SolveError> {
+    max_pair_magnitude(numbers)
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = DAY;
+    type Input = Vec<SnailfishNumber>;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn number(s: &str) -> SnailfishNumber {
+        SnailfishNumber::from_str(s).unwrap()
+    }
+
+    fn numbers(lines: &[&str]) -> Vec<SnailfishNumber> {
+        lines.iter().map(|line| number(line)).collect()
+    }
+
+    fn exploded(s: &str) -> String {
+        let mut n = number(s);
+        assert_eq!(n.explode(), Some(true));
+        n.to_string()
+    }
+
+    #[test]
+    pub fn test_parse_display() {
+        let s = "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]";
+
+        assert_eq!(number(s).to_string(), s);
+        assert_eq!(number("[9,[8,7]]"),
+            SnailfishNumber::pair(Regular(9), SnailfishNumber::pair(Regular(8), Regular(7))));
+    }
+
+    #[test]
+    pub fn test_parse_errors() {
+        let error = SnailfishNumber::from_str("[[1,2],3").unwrap_err();
+        assert_eq!((error.column, error.expected), (9, "']'"));
+
+        let error = SnailfishNumber::from_str("[1;2]").unwrap_err();
+        assert_eq!((error.column, error.token.as_str()), (3, ";"));
+
+        let error = SnailfishNumber::from_str("[1,2]]").unwrap_err();
+        assert_eq!((error.column, error.expected), (6, "end of line"));
+    }
+
+    #[test]
+    pub fn test_explode() {
+        assert_eq!(exploded("[[[[[9,8],1],2],3],4]"), "[[[[0,9],2],3],4]");
+        assert_eq!(exploded("[7,[6,[5,[4,[3,2]]]]]"), "[7,[6,[5,[7,0]]]]");
+        assert_eq!(exploded("[[6,[5,[4,[3,2]]]],1]"), "[[6,[5,[7,0]]],3]");
+        assert_eq!(exploded("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]"), "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]");
+        assert_eq!(exploded("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"), "[[3,[2,[8,0]]],[9,[5,[7,0]]]]");
+        assert_eq!(number("[[[[0,9],2],3],4]").explode(), Some(false));
+    }
+
+    #[test]
+    pub fn test_split() {
+        let mut n = number("[[[[0,7],4],[15,[0,13]]],[1,1]]");
+
+        assert!(n.split());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]");
+        assert!(n.split());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]");
+        assert!(!number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").split());
+    }
+
+    #[test]
+    pub fn test_reduction_steps() {
+        let mut n = SnailfishNumber::pair(number("[[[[4,3],4],4],[7,[[8,4],9]]]"), number("[1,1]"));
+        let steps = [
+            "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+            "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+            "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+            "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+        ];
+
+        for step in steps {
+            assert!(n.explode().unwrap() || n.split());
+            assert_eq!(n.to_string(), step);
+        }
+        assert!(!n.explode().unwrap() && !n.split());
+    }
+
+    #[test]
+    pub fn test_add() {
+        let sum = number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]");
+
+        assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    }
+
+    #[test]
+    pub fn test_sum() {
+        let list = numbers(&["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]);
+
+        assert_eq!(sum(&list[..4]).unwrap().to_string(), "[[[[1,1],[2,2]],[3,3]],[4,4]]");
+        assert_eq!(sum(&list[..5]).unwrap().to_string(), "[[[[3,0],[5,3]],[4,4]],[5,5]]");
+        assert_eq!(sum(&list).unwrap().to_string(), "[[[[5,0],[7,4]],[5,5]],[6,6]]");
+        assert!(sum(&[]).is_err());
+    }
+
+    #[test]
+    pub fn test_sum_larger_example() {
+        let list = numbers(&[
+            "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+            "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+            "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+            "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+            "[7,[5,[[3,8],[1,4]]]]",
+            "[[2,[2,2]],[8,[8,1]]]",
+            "[2,9]",
+            "[1,[[[9,3],9],[[9,0],[0,7]]]]",
+            "[[[5,[7,4]],7],1]",
+            "[[[[4,2],2],6],[8,7]]",
+        ]);
+
+        assert_eq!(sum(&list).unwrap().to_string(), "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]");
+    }
+
+    #[test]
+    pub fn test_magnitude() {
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), Some(143));
+        assert_eq!(number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").magnitude(), Some(1384));
+        assert_eq!(number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(), Some(3488));
+    }
+
+    #[test]
+    pub fn test_overflow() {
+        let carrying = numbers(&["[[[[1,4294967295],4294967295],0],0]", "[1,1]"]);
+        let deep = number(&format!("{}1{}", "[".repeat(41), ",0]".repeat(41)));
+
+        assert_eq!(carrying[0].clone().checked_add(carrying[1].clone()), None);
+        assert_eq!(solve_part1(&carrying).unwrap_err().to_string(), "day 18: regular number does not fit in 32 bits");
+        assert_eq!(deep.magnitude(), None);
+        assert_eq!(solve_part1(&[deep]).unwrap_err().to_string(), "day 18: magnitude does not fit in 64 bits");
+        assert_eq!(solve_part2(&carrying[..1]).unwrap_err().to_string(), "day 18: there are fewer than two numbers");
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::error::{ParseError, SolveError, Source};
+use crate::geometry::{Rotation, Vec3};
+use crate::solution::Solution;
+
+const DAY: u8 = 19;
+
+/// Number of beacons two scanners need to have in common to be aligned
+pub const MIN_OVERLAP: usize = 12;
+/// Largest beacon coordinate relative to its scanner. Scanners only detect
+/// beacons up to 1000 units away, and the bound keeps squared distances and
+/// scanner positions well within an i64.
+pub const MAX_COORDINATE: i64 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanner {
+    pub id: usize,
+    /// Beacon positions relative to the scanner, in its own orientation
+    pub beacons: Vec<Vec3>,
+}
+
+/// Position and orientation of a scanner within the frame of another one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pose {
+    pub rotation: Rotation,
+    pub position: Vec3,
+}
+
+/// All beacons in the frame of the first scanner, along with the poses of all
+/// scanners within that frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconMap {
+    pub poses: Vec<Pose>,
+    pub beacons: BTreeSet<Vec3>,
+}
+
+impl Scanner {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Scanner, ParseError> {
+        let (header, beacons_str) = s.split_once('\n').unwrap_or((s, ""));
+        let id_str = header
+            .strip_prefix("--- scanner ")
+            .and_then(|rest| rest.strip_suffix(" ---"))
+            .ok_or_else(|| source.error(header, "--- scanner N ---"))?;
+        let id = source.parse::<usize>(id_str, "scanner number")?;
+
+        let beacons = beacons_str
+            .lines()
+            .map(|line| {
+                let beacon = Vec3::parse(source, line)?;
+                if [beacon.x, beacon.y, beacon.z].iter().any(|c| c.unsigned_abs() > MAX_COORDINATE as u64) {
+                    return Err(source.error(line, "coordinates from -1000000 to 1000000"));
+                }
+                Ok(beacon)
+            })
+            .collect::<Result<Vec<Vec3>, ParseError>>()?;
+
+        Ok(Scanner { id, beacons })
+    }
+
+    // Squared distances between all pairs of beacons, which do not depend on the
+    // scanner's pose
+    fn fingerprint(&self) -> Vec<i64> {
+        let mut distances = Vec::new();
+        for (i, a) in self.beacons.iter().enumerate() {
+            distances.extend(self.beacons[i + 1..].iter().map(|b| (*a - *b).squared_length()));
+        }
+        distances.sort_unstable();
+        distances
+    }
+}
+
+// Number of values two sorted lists have in common, counting duplicates
+fn count_common(a: &[i64], b: &[i64]) -> usize {
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            },
+        }
+    }
+    common
+}
+
+impl Pose {
+    pub const ORIGIN: Pose = Pose { rotation: Rotation::IDENTITY, position: Vec3 { x: 0, y: 0, z: 0 } };
+
+    /// Maps coordinates relative to the posed scanner into the outer frame
+    pub fn transform(&self, v: Vec3) -> Vec3 {
+        self.rotation.apply(v) + self.position
+    }
+
+    /// Pose in the outer frame of a scanner that has the pose `inner` within
+    /// the frame of the scanner posed by `self`
+    pub fn then(&self, inner: &Pose) -> Pose {
+        Pose { rotation: self.rotation.then_after(&inner.rotation), position: self.transform(inner.position) }
+    }
+}
+
+/// Pose of the scanner that reported `other` within the frame of the scanner
+/// that reported `reference`, if at least `MIN_OVERLAP` beacons coincide
+pub fn align(reference: &[Vec3], other: &[Vec3]) -> Option<Pose> {
+    for rotation in Rotation::all() {
+        let mut offsets: HashMap<Vec3, usize> = HashMap::new();
+        for b in other.iter().map(|b| rotation.apply(*b)) {
+            for a in reference {
+                let count = offsets.entry(*a - b).or_insert(0);
+                *count += 1;
+                if *count >= MIN_OVERLAP {
+                    return Some(Pose { rotation, position: *a - b });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Aligns all scanners with the first one, either directly or through other
+/// scanners. Returns `None` if some scanner cannot be aligned at all.
+pub fn build_map(scanners: &[Scanner]) -> Option<BeaconMap> {
+    // Aligned scanners have to share the distances between their common beacons
+    let min_common = MIN_OVERLAP * (MIN_OVERLAP - 1) / 2;
+    let fingerprints = scanners.iter().map(Scanner::fingerprint).collect::<Vec<Vec<i64>>>();
+
+    let mut poses = vec![None; scanners.len()];
+    let mut queue = Vec::new();
+    if !scanners.is_empty() {
+        poses[0] = Some(Pose::ORIGIN);
+        queue.push(0);
+    }
+
+    while let Some(i) = queue.pop() {
+        let pose = poses[i].unwrap();
+        for j in 0..scanners.len() {
+            if poses[j].is_some() || count_common(&fingerprints[i], &fingerprints[j]) < min_common {
+                continue;
+            }
+            if let Some(relative) = align(&scanners[i].beacons, &scanners[j].beacons) {
+                poses[j] = Some(pose.then(&relative));
+                queue.push(j);
+            }
+        }
+    }
+
+    let poses = poses.into_iter().collect::<Option<Vec<Pose>>>()?;
+    let beacons = scanners
+        .iter()
+        .zip(&poses)
+        .flat_map(|(scanner, pose)| scanner.beacons.iter().map(|b| pose.transform(*b)))
+        .collect();
+
+    Some(BeaconMap { poses, beacons })
+}
+
+impl BeaconMap {
+    /// Largest Manhattan distance between any two scanners
+    pub fn max_scanner_distance(&self) -> i64 {
+        self.poses
+            .iter()
+            .flat_map(|a| self.poses.iter().map(|b| a.position.manhattan(&b.position)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[aoc_generator(day19)]
+pub fn input_generator(input: &str) -> Result<Vec<Scanner>, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .split("\n\n")
+        .map(|block| Scanner::parse(&source, block))
+        .collect::<Result<Vec<Scanner>, ParseError>>()
+}
+
+fn aligned_map(scanners: &[Scanner]) -> Result<BeaconMap, SolveError> {
+    build_map(scanners).ok_or_else(|| SolveError::new(DAY, "scanners cannot all be aligned"))
+}
+
+#[aoc(day19, part1)]
+pub fn solve_part1(scanners: &[Scanner]) -> Result<i64, SolveError> {
+    Ok(aligned_map(scanners)?.beacons.len() as i64)
+}
+
+#[aoc(day19, part2)]
+pub fn solve_part2(scanners: &[Scanner]) -> Result<i64, SolveError> {
+    Ok(aligned_map(scanners)?.max_scanner_distance())
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Scanner>;
+    type Output = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // The first scanner's beacons, and the same ones as seen by a second scanner
+    // that is turned and placed at (100, -200, 300) relative to the first
+    fn scanner_pair() -> (Vec<Vec3>, Vec<Vec3>, Pose) {
+        let beacons = (0..MIN_OVERLAP as i64)
+            .map(|i| Vec3::new(i * 37 % 101, i * i - 50, 7 - 13 * i))
+            .collect::<Vec<Vec3>>();
+        let pose = Pose { rotation: Rotation::all()[17], position: Vec3::new(100, -200, 300) };
+        let inverse = pose.rotation.inverse();
+        let seen = beacons.iter().map(|b| inverse.apply(*b - pose.position)).collect();
+
+        (beacons, seen, pose)
+    }
+
+    #[test]
+    pub fn test_input_generator() {
+        let scanners = input_generator("--- scanner 0 ---\n404,-588,-901\n528,-643,409\n\n--- scanner 1 ---\n686,422,578").unwrap();
+
+        assert_eq!(scanners.len(), 2);
+        assert_eq!(scanners[0].beacons[1], Vec3::new(528, -643, 409));
+        assert_eq!(scanners[1].id, 1);
+    }
+
+    #[test]
+    pub fn test_input_generator_invalid_header() {
+        let error = input_generator("--- scanner 0 ---\n404,-588,-901\n\n--- scaner 1 ---\n686,422,578").unwrap_err();
+
+        assert_eq!((error.line, error.column, error.expected), (4, 1, "--- scanner N ---"));
+
+        let error = input_generator("--- scanner 0 ---\n1,2,3\n-9223372036854775808,0,0").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (3, 1, "coordinates from -1000000 to 1000000"));
+    }
+
+    #[test]
+    pub fn test_align() {
+        let (beacons, seen, pose) = scanner_pair();
+
+        assert_eq!(align(&beacons, &seen), Some(pose));
+        assert_eq!(align(&beacons, &seen[1..]), None);
+    }
+
+    #[test]
+    pub fn test_build_map() {
+        let (beacons, seen, pose) = scanner_pair();
+        let scanners = vec![Scanner { id: 0, beacons: beacons.clone() }, Scanner { id: 1, beacons: seen }];
+
+        let map = build_map(&scanners).unwrap();
+
+        assert_eq!(map.poses, vec![Pose::ORIGIN, pose]);
+        assert_eq!(map.beacons, BTreeSet::from_iter(beacons));
+        assert_eq!(map.max_scanner_distance(), 600);
+    }
+
+    #[test]
+    pub fn test_solve_unaligned() {
+        let scanners = input_generator("--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n4,5,6").unwrap();
+
+        let error = solve_part1(&scanners).unwrap_err();
+
+        assert_eq!(error.to_string(), "day 19: scanners cannot all be aligned");
+    }
+
+    #[test]
+    pub fn test_pose_then() {
+        let (_, _, pose) = scanner_pair();
+        let v = Vec3::new(1, 2, 3);
+
+        assert_eq!(pose.then(&pose).transform(v), pose.transform(pose.transform(v)));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::grid::Grid;
+use crate::solution::Solution;
+
+const DAY: u8 = 20;
+const ALGORITHM_LEN: usize = 512;
+
+fn pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+/// Image with a finite area of interest, surrounded by an infinite background
+/// of uniform colour
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub pixels: Grid<bool>,
+    pub background: bool,
+}
+
+#[derive(Debug)]
+pub struct Input {
+    pub algorithm: Vec<bool>,
+    pub image: Image,
+}
+
+impl Image {
+    fn get(&self, x: i64, y: i64) -> bool {
+        if x < 0 || y < 0 {
+            return self.background;
+        }
+        *self.pixels.get(x as usize, y as usize).unwrap_or(&self.background)
+    }
+
+    /// Applies the enhancement algorithm once. The image grows by one pixel on
+    /// every side, and the background changes to whatever the algorithm makes
+    /// of a 3x3 square of background pixels.
+    pub fn enhance(&self, algorithm: &[bool]) -> Image {
+        let (width, height) = (self.pixels.width() + 2, self.pixels.height() + 2);
+        let mut pixels = Grid::new(width, height, false);
+
+        for y in 0..height {
+            for x in 0..width {
+                // Output pixel (x, y) is centered on input pixel (x - 1, y - 1)
+                let (cx, cy) = (x as i64 - 1, y as i64 - 1);
+                let index = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                    .fold(0, |index, (dx, dy)| (index << 1) | self.get(cx + dx, cy + dy) as usize);
+                pixels[(x, y)] = algorithm[index];
+            }
+        }
+
+        let background = algorithm[if self.background { ALGORITHM_LEN - 1 } else { 0 }];
+        Image { pixels, background }
+    }
+
+    pub fn enhance_times(&self, algorithm: &[bool], steps: usize) -> Image {
+        (0..steps).fold(self.clone(), |image, _| image.enhance(algorithm))
+    }
+
+    /// Number of lit pixels, or `None` if there are infinitely many
+    pub fn lit_count(&self) -> Option<usize> {
+        if self.background {
+            None
+        } else {
+            Some(self.pixels.cells().filter(|lit| **lit).count())
+        }
+    }
+
+    /// Renders the image with `margin` pixels of background on every side
+    pub fn render(&self, margin: usize) -> String {
+        let (width, height) = (self.pixels.width() + 2 * margin, self.pixels.height() + 2 * margin);
+        let mut framed = Grid::new(width, height, self.background);
+        for ((x, y), lit) in self.pixels.iter() {
+            framed[(x + margin, y + margin)] = *lit;
+        }
+
+        framed.render(|lit| if *lit { '#' } else { '.' })
+    }
+}
+
+#[aoc_generator(day20)]
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let source = Source::new(DAY, input);
+    let (algorithm_str, image_str) = source.split_once(input, "\n\n", "blank line followed by image")?;
+
+    let algorithm = algorithm_str
+        .char_indices()
+        .map(|(pos, c)| pixel(c).ok_or_else(|| source.error(&algorithm_str[pos..pos + c.len_utf8()], "'#' or '.'")))
+        .collect::<Result<Vec<bool>, ParseError>>()?;
+    if algorithm.len() != ALGORITHM_LEN {
+        return Err(source.error(algorithm_str, "algorithm of 512 pixels"));
+    }
+
+    let pixels = Grid::parse_chars(&source, image_str, "'#' or '.'", pixel)?;
+
+    Ok(Input { algorithm, image: Image { pixels, background: false } })
+}
+
+fn lit_after(input: &Input, steps: usize) -> Result<usize, SolveError> {
+    input.image
+        .enhance_times(&input.algorithm, steps)
+        .lit_count()
+        .ok_or_else(|| SolveError::new(DAY, "infinitely many pixels are lit"))
+}
+
+#[aoc(day20, part1)]
+pub fn solve_part1(input: &Input) -> Result<usize, SolveError> {
+    lit_after(input, 2)
+}
+
+#[aoc(day20, part2)]
+pub fn solve_part2(input: &Input) -> Result<usize, SolveError> {
+    lit_after(input, 50)
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = DAY;
+    type Input = Input;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Algorithm that sets every pixel to `f` of its previous value
+    fn algorithm_str(f: fn(bool) -> bool) -> String {
+        (0..ALGORITHM_LEN).map(|index| if f(index & 0b10000 != 0) { '#' } else { '.' }).collect()
+    }
+
+    fn input(f: fn(bool) -> bool) -> Input {
+        input_generator(&format!("{}\n\n#..\n.#.\n..#", algorithm_str(f))).unwrap()
+    }
+
+    #[test]
+    pub fn test_enhance_identity() {
+        let input = input(|lit| lit);
+
+        let image = input.image.enhance_times(&input.algorithm, 2);
+
+        assert_eq!(image.lit_count(), Some(3));
+        assert_eq!(image.render(0), ".......\n.......\n..#....\n...#...\n....#..\n.......\n.......\n");
+    }
+
+    #[test]
+    pub fn test_enhance_flipping_background() {
+        let input = input(|lit| !lit);
+
+        let once = input.image.enhance(&input.algorithm);
+        let twice = once.enhance(&input.algorithm);
+
+        assert!(once.background);
+        assert_eq!(once.lit_count(), None);
+        assert_eq!(once.render(1), "#######\n#######\n##.####\n###.###\n####.##\n#######\n#######\n");
+        assert!(!twice.background);
+        assert_eq!(twice.lit_count(), Some(3));
+    }
+
+    #[test]
+    pub fn test_solve_infinitely_many_lit() {
+        let input = input(|_| true);
+
+        let error = solve_part1(&input).unwrap_err();
+
+        assert_eq!(error.to_string(), "day 20: infinitely many pixels are lit");
+    }
+
+    #[test]
+    pub fn test_input_generator_short_algorithm() {
+        let error = input_generator("#.#\n\n#..\n.#.").unwrap_err();
+
+        assert_eq!((error.line, error.column, error.expected), (1, 1, "algorithm of 512 pixels"));
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 21;
+const PLAYERS: usize = 2;
+const ROLLS_PER_TURN: u32 = 3;
+const BOARD_SIZE: u32 = 10;
+
+/// Sums of three rolls of a three-sided die, along with the number of
+/// universes each of them happens in
+const DIRAC_ROLLS: [(u32, u128); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    board_size: u32,
+    winning_score: u32,
+}
+
+impl Rules {
+    pub const DETERMINISTIC: Rules = Rules { board_size: BOARD_SIZE, winning_score: 1000 };
+    pub const DIRAC: Rules = Rules { board_size: BOARD_SIZE, winning_score: 21 };
+
+    /// Rules for a circular board of `board_size` spaces, numbered from 1, or
+    /// `None` if the board has no spaces at all. As scores can end up to one
+    /// board size above the winning score, both have to add up to a u32.
+    pub fn new(board_size: u32, winning_score: u32) -> Option<Rules> {
+        (board_size > 0 && board_size.checked_add(winning_score).is_some())
+            .then_some(Rules { board_size, winning_score })
+    }
+
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    pub fn winning_score(&self) -> u32 {
+        self.winning_score
+    }
+
+    fn on_board(&self, positions: &[u32; PLAYERS]) -> bool {
+        positions.iter().all(|position| (1..=self.board_size).contains(position))
+    }
+
+    fn advance(&self, position: u32, steps: u32) -> u32 {
+        ((position as u64 - 1 + steps as u64) % self.board_size as u64) as u32 + 1
+    }
+}
+
+/// Final state of a game played with the deterministic 100-sided die
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    pub scores: [u32; PLAYERS],
+    pub rolls: u64,
+}
+
+#[aoc_generator(day21)]
+pub fn input_generator(input: &str) -> Result<[u32; PLAYERS], ParseError> {
+    let source = Source::new(DAY, input);
+    let lines = input.lines().collect::<Vec<&str>>();
+    if lines.len() != PLAYERS {
+        return Err(source.error(lines.get(PLAYERS).unwrap_or(&&input[input.len()..]), "one line per player"));
+    }
+
+    let mut positions = [0; PLAYERS];
+    for (player, line) in lines.iter().enumerate() {
+        let (header, position_str) = source.split_once(line, ": ", "': ' followed by starting position")?;
+        if *header != format!("Player {} starting position", player + 1) {
+            return Err(source.error(header, "Player N starting position"));
+        }
+
+        positions[player] = source.parse::<u32>(position_str, "starting position")?;
+        if !(1..=BOARD_SIZE).contains(&positions[player]) {
+            return Err(source.error(position_str, "starting position from 1 to 10"));
+        }
+    }
+
+    Ok(positions)
+}
+
+/// Plays with a die that rolls 1, 2, ..., 100, 1, ... until a player wins
+pub fn play_deterministic(start: [u32; PLAYERS], rules: &Rules) -> Result<Outcome, SolveError> {
+    if !rules.on_board(&start) {
+        return Err(off_board());
+    }
+
+    let mut positions = start;
+    let mut scores = [0; PLAYERS];
+    let mut rolls = 0;
+
+    for player in (0..PLAYERS).cycle() {
+        let steps = (0..ROLLS_PER_TURN).map(|i| ((rolls + i as u64) % 100) as u32 + 1).sum();
+        rolls += ROLLS_PER_TURN as u64;
+
+        positions[player] = rules.advance(positions[player], steps);
+        scores[player] += positions[player];
+        if scores[player] >= rules.winning_score {
+            break;
+        }
+    }
+
+    Ok(Outcome { scores, rolls })
+}
+
+type UniverseKey = ([u32; PLAYERS], [u32; PLAYERS], usize);
+
+// None if a number of universes does not fit in a u128
+fn count_wins_from(rules: &Rules, key: UniverseKey, memo: &mut HashMap<UniverseKey, [u128; PLAYERS]>)
+    -> Option<[u128; PLAYERS]> {
+    if let Some(wins) = memo.get(&key) {
+        return Some(*wins);
+    }
+
+    let (positions, scores, turn) = key;
+    let mut wins = [0u128; PLAYERS];
+    for (steps, universes) in DIRAC_ROLLS {
+        let mut positions = positions;
+        let mut scores = scores;
+        positions[turn] = rules.advance(positions[turn], steps);
+        scores[turn] += positions[turn];
+
+        if scores[turn] >= rules.winning_score {
+            wins[turn] = wins[turn].checked_add(universes)?;
+        } else {
+            let next = count_wins_from(rules, (positions, scores, (turn + 1) % PLAYERS), memo)?;
+            for (total, w) in wins.iter_mut().zip(next) {
+                *total = total.checked_add(universes.checked_mul(w)?)?;
+            }
+        }
+    }
+
+    memo.insert(key, wins);
+    Some(wins)
+}
+
+/// Number of universes in which each player wins when playing with the
+/// three-sided Dirac die, which splits the universe on every roll
+pub fn count_wins(start: [u32; PLAYERS], rules: &Rules) -> Result<[u128; PLAYERS], SolveError> {
+    if !rules.on_board(&start) {
+        return Err(off_board());
+    }
+
+    count_wins_from(rules, (start, [0; PLAYERS], 0), &mut HashMap::new())
+        .ok_or_else(|| SolveError::new(DAY, "number of universes does not fit in 128 bits"))
+}
+
+fn off_board() -> SolveError {
+    SolveError::new(DAY, "starting position is not on the board")
+}
+
+#[aoc(day21, part1)]
+pub fn solve_part1(start: &[u32; PLAYERS]) -> Result<u128, SolveError> {
+    let outcome = play_deterministic(*start, &Rules::DETERMINISTIC)?;
+    let losing_score = outcome.scores.iter().min().unwrap();
+
+    Ok(*losing_score as u128 * outcome.rolls as u128)
+}
+
+#[aoc(day21, part2)]
+pub fn solve_part2(start: &[u32; PLAYERS]) -> Result<u128, SolveError> {
+    let wins = count_wins(*start, &Rules::DIRAC)?;
+
+    Ok(*wins.iter().max().unwrap())
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = DAY;
+    type Input = [u32; PLAYERS];
+    type Output = u128;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub fn sample_str() -> String {
+        String::from("Player 1 starting position: 4
+Player 2 starting position: 8")
+    }
+
+    #[test]
+    pub fn test_input_generator() {
+        assert_eq!(input_generator(sample_str().as_str()).unwrap(), [4, 8]);
+
+        let error = input_generator("Player 1 starting position: 4\nPlayer 3 starting position: 8").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (2, 1, "Player N starting position"));
+
+        for position in ["0", "11"] {
+            let input = format!("Player 1 starting position: 4\nPlayer 2 starting position: {}", position);
+            let error = input_generator(&input).unwrap_err();
+            assert_eq!((error.line, error.column, error.expected), (2, 29, "starting position from 1 to 10"));
+        }
+    }
+
+    #[test]
+    pub fn test_rules_invalid() {
+        assert_eq!(Rules::new(0, 21), None);
+        assert_eq!(Rules::new(10, u32::MAX - 9), None);
+        assert!(play_deterministic([0, 8], &Rules::DETERMINISTIC).is_err());
+        assert!(count_wins([4, 3], &Rules::new(2, 21).unwrap()).is_err());
+    }
+
+    #[test]
+    pub fn test_play_deterministic_large_board() {
+        let rules = Rules::new(u32::MAX - 2, 2).unwrap();
+
+        let outcome = play_deterministic([u32::MAX - 2, 1], &rules);
+
+        assert_eq!(outcome, Ok(Outcome { scores: [6, 0], rolls: 3 }));
+    }
+
+    #[test]
+    pub fn test_count_wins_large() {
+        // Both used to overflow u64 counts
+        for winning_score in [30, 40] {
+            let wins = count_wins([4, 8], &Rules::new(10, winning_score).unwrap()).unwrap();
+            assert!(wins[0] + wins[1] > u64::MAX as u128);
+        }
+
+        // Every move scores a single point, so each game lasts for 79 turns
+        let error = count_wins([1, 1], &Rules::new(1, 40).unwrap()).unwrap_err();
+        assert_eq!(error.to_string(), "day 21: number of universes does not fit in 128 bits");
+    }
+
+    #[test]
+    pub fn test_play_deterministic() {
+        let outcome = play_deterministic([4, 8], &Rules::DETERMINISTIC);
+
+        assert_eq!(outcome, Ok(Outcome { scores: [1000, 745], rolls: 993 }));
+    }
+
+    #[test]
+    pub fn test_count_wins() {
+        assert_eq!(count_wins([4, 8], &Rules::DIRAC), Ok([444356092776315, 341960390180808]));
+    }
+
+    #[test]
+    pub fn test_count_wins_variant() {
+        // Any first move wins, in each of the 27 universes after three rolls
+        assert_eq!(count_wins([4, 8], &Rules::new(10, 1).unwrap()), Ok([27, 0]));
+        // On a single-space board, every move scores exactly one point
+        assert_eq!(count_wins([1, 1], &Rules::new(1, 2).unwrap()), Ok([27 * 27 * 27, 0]));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::geometry::{Cuboid, CuboidSet, Vec3};
+use crate::solution::Solution;
+
+const DAY: u8 = 22;
+const INITIALIZATION_RADIUS: i64 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
+}
+
+impl Step {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Step, ParseError> {
+        let (state, cuboid_str) = source.split_once(s, " ", "' ' followed by cuboid")?;
+        let on = match state {
+            "on" => true,
+            "off" => false,
+            _ => return Err(source.error(state, "on or off")),
+        };
+
+        Ok(Step { on, cuboid: Cuboid::parse(source, cuboid_str)? })
+    }
+}
+
+#[aoc_generator(day22)]
+pub fn input_generator(input: &str) -> Result<Vec<Step>, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|line| Step::parse(&source, line))
+        .collect::<Result<Vec<Step>, ParseError>>()
+}
+
+/// Cubes that are on after performing all steps
+pub fn reboot(steps: &[Step]) -> CuboidSet {
+    let mut cubes = CuboidSet::new();
+    for step in steps {
+        if step.on {
+            cubes.insert(step.cuboid);
+        } else {
+            cubes.remove(step.cuboid);
+        }
+    }
+    cubes
+}
+
+fn volume(cubes: &CuboidSet) -> Result<i128, SolveError> {
+    cubes.volume().ok_or_else(|| SolveError::new(DAY, "number of cubes does not fit in 128 bits"))
+}
+
+#[aoc(day22, part1)]
+pub fn solve_part1(steps: &[Step]) -> Result<i128, SolveError> {
+    let r = INITIALIZATION_RADIUS;
+    let region = Cuboid::new(Vec3::new(-r, -r, -r), Vec3::new(r, r, r));
+
+    // Clipping first keeps cuboids far outside the region from adding terms
+    let clipped = steps
+        .iter()
+        .filter_map(|step| step.cuboid.intersection(&region).map(|cuboid| Step { cuboid, ..*step }))
+        .collect::<Vec<Step>>();
+
+    volume(&reboot(&clipped))
+}
+
+#[aoc(day22, part2)]
+pub fn solve_part2(steps: &[Step]) -> Result<i128, SolveError> {
+    volume(&reboot(steps))
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Step>;
+    type Output = i128;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_input_generator() {
+        let steps = input_generator("on x=-20..26,y=-36..17,z=-47..7\noff x=9..11,y=9..11,z=9..11").unwrap();
+
+        assert_eq!(steps[0], Step { on: true, cuboid: Cuboid::new(Vec3::new(-20, -36, -47), Vec3::new(26, 17, 7)) });
+        assert!(!steps[1].on);
+    }
+
+    #[test]
+    pub fn test_input_generator_invalid() {
+        let error = input_generator("on x=10..12,y=10..12,z=10..12\nof x=9..11,y=9..11,z=9..11").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (2, 1, "on or off"));
+
+        let error = input_generator("on x=10..12,y=10..12,w=10..12").unwrap_err();
+        assert_eq!((error.column, error.expected), (22, "z="));
+    }
+
+    #[test]
+    pub fn test_part1_ignores_outer_cubes() {
+        let steps = input_generator("on x=-54..-47,y=-10..10,z=0..0\noff x=-100..100,y=-100..-1,z=-100..100").unwrap();
+
+        assert_eq!(solve_part1(&steps), Ok(4 * 11));
+        assert_eq!(solve_part2(&steps), Ok(8 * 11));
+    }
+
+    #[test]
+    pub fn test_large_cuboids() {
+        let steps = input_generator("on x=-3000000..3000000,y=-3000000..3000000,z=-3000000..3000000").unwrap();
+        let full_range = format!("on x={0}..{1},y={0}..{1},z={0}..{1}", i64::MIN, i64::MAX);
+
+        assert_eq!(solve_part1(&steps), Ok(101 * 101 * 101));
+        assert_eq!(solve_part2(&steps), Ok(6000001i128.pow(3)));
+        assert_eq!(solve_part2(&input_generator(&full_range).unwrap()).unwrap_err().to_string(),
+            "day 22: number of cubes does not fit in 128 bits");
+    }
+}
@@ -0,0 +1,474 @@
+use std::fmt;
+use crate::error::{ParseError, SolveError, Source};
+use crate::graph;
+use crate::solution::Solution;
+
+const DAY: u8 = 23;
+const HALLWAY_LEN: usize = 11;
+const ROOMS: usize = 4;
+
+/// Deepest rooms supported, as found in the unfolded diagram
+pub const MAX_DEPTH: usize = 4;
+
+// Rows of the diagram, where '?' marks a space that may hold an amphipod
+const TOP_WALL: &str = "#############";
+const HALLWAY_ROW: &str = "#???????????#";
+const FIRST_ROOM_ROW: &str = "###?#?#?#?###";
+const ROOM_ROW: &str = "  #?#?#?#?#";
+const BOTTOM_WALL: &str = "  #########";
+
+/// Rows inserted below the first room row when unfolding the diagram
+const FOLDED_ROWS: [[Amphipod; ROOMS]; 2] = [
+    [Amphipod::D, Amphipod::C, Amphipod::B, Amphipod::A],
+    [Amphipod::D, Amphipod::B, Amphipod::A, Amphipod::C],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Amphipod {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Amphipod {
+    const ALL: [Amphipod; ROOMS] = [Amphipod::A, Amphipod::B, Amphipod::C, Amphipod::D];
+
+    fn from_char(c: char) -> Option<Amphipod> {
+        match c {
+            'A' => Some(Amphipod::A),
+            'B' => Some(Amphipod::B),
+            'C' => Some(Amphipod::C),
+            'D' => Some(Amphipod::D),
+            _ => None,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Amphipod::A => 'A',
+            Amphipod::B => 'B',
+            Amphipod::C => 'C',
+            Amphipod::D => 'D',
+        }
+    }
+
+    /// Energy required to move one step
+    pub fn energy(&self) -> usize {
+        match self {
+            Amphipod::A => 1,
+            Amphipod::B => 10,
+            Amphipod::C => 100,
+            Amphipod::D => 1000,
+        }
+    }
+
+    /// Index of the room this amphipod belongs in, counting from the left
+    pub fn room(&self) -> usize {
+        *self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// Space in the hallway, counting from the left
+    Hallway(usize),
+    /// Space within a room, where slot 0 is the one next to the hallway
+    Room { room: usize, slot: usize },
+}
+
+impl Location {
+    // Coordinates with the hallway at y = 0
+    fn coords(&self) -> (usize, usize) {
+        match *self {
+            Location::Hallway(x) => (x, 0),
+            Location::Room { room, slot } => (door(room), slot + 1),
+        }
+    }
+}
+
+/// Hallway space right outside of a room, which amphipods never stop on
+fn door(room: usize) -> usize {
+    2 + 2 * room
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub amphipod: Amphipod,
+    pub from: Location,
+    pub to: Location,
+}
+
+impl Move {
+    pub fn steps(&self) -> usize {
+        let ((x1, y1), (x2, y2)) = (self.from.coords(), self.to.coords());
+        x1.abs_diff(x2) + y1 + y2
+    }
+
+    pub fn energy(&self) -> usize {
+        self.steps() * self.amphipod.energy()
+    }
+}
+
+/// Positions of all amphipods, which is small enough to be used as a search node
+/// directly. Rooms only use their first `depth` slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Burrow {
+    pub depth: usize,
+    pub hallway: [Option<Amphipod>; HALLWAY_LEN],
+    pub rooms: [[Option<Amphipod>; MAX_DEPTH]; ROOMS],
+}
+
+/// Cheapest sequence of moves that organizes a burrow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub moves: Vec<Move>,
+    pub energy: usize,
+}
+
+impl Burrow {
+    pub fn get(&self, location: Location) -> Option<Amphipod> {
+        match location {
+            Location::Hallway(x) => self.hallway[x],
+            Location::Room { room, slot } => self.rooms[room][slot],
+        }
+    }
+
+    fn set(&mut self, location: Location, amphipod: Option<Amphipod>) {
+        match location {
+            Location::Hallway(x) => self.hallway[x] = amphipod,
+            Location::Room { room, slot } => self.rooms[room][slot] = amphipod,
+        }
+    }
+
+    pub fn apply(&self, m: &Move) -> Burrow {
+        let mut next = *self;
+        next.set(m.from, None);
+        next.set(m.to, Some(m.amphipod));
+        next
+    }
+
+    pub fn is_organized(&self) -> bool {
+        (0..ROOMS).all(|room| self.is_settled(room, 0))
+    }
+
+    /// Inserts the two folded rows below the first room row
+    pub fn unfold(&self) -> Option<Burrow> {
+        if self.depth + FOLDED_ROWS.len() > MAX_DEPTH {
+            return None;
+        }
+
+        let mut unfolded = *self;
+        unfolded.depth += FOLDED_ROWS.len();
+        for room in 0..ROOMS {
+            let mut slots = vec![self.rooms[room][0]];
+            slots.extend(FOLDED_ROWS.iter().map(|row| Some(row[room])));
+            slots.extend(&self.rooms[room][1..self.depth]);
+            unfolded.rooms[room][..slots.len()].copy_from_slice(&slots);
+        }
+        Some(unfolded)
+    }
+
+    // Whether the amphipods from `slot` down are in their own room
+    fn is_settled(&self, room: usize, slot: usize) -> bool {
+        self.rooms[room][slot..self.depth].iter().all(|a| a.is_some_and(|a| a.room() == room))
+    }
+
+    // Slot an amphipod moves into when entering its own room, unless strangers are still inside
+    fn entry(&self, amphipod: Amphipod) -> Option<Location> {
+        let room = amphipod.room();
+        let slots = &self.rooms[room][..self.depth];
+        if slots.iter().flatten().any(|a| *a != amphipod) {
+            return None;
+        }
+        slots.iter().rposition(|a| a.is_none()).map(|slot| Location::Room { room, slot })
+    }
+
+    // Whether the hallway between the two spaces is empty, not counting `from` itself
+    fn hallway_clear(&self, from: usize, to: usize) -> bool {
+        (from.min(to)..=from.max(to)).all(|x| x == from || self.hallway[x].is_none())
+    }
+
+    /// All legal moves. An amphipod that can reach its own room is always moved
+    /// there right away, which never makes the plan more expensive.
+    pub fn moves(&self) -> Vec<Move> {
+        for (x, amphipod) in self.hallway.iter().enumerate() {
+            if let Some(amphipod) = *amphipod {
+                if let Some(to) = self.entry(amphipod) {
+                    if self.hallway_clear(x, door(amphipod.room())) {
+                        return vec![Move { amphipod, from: Location::Hallway(x), to }];
+                    }
+                }
+            }
+        }
+
+        let mut moves = Vec::new();
+        for room in 0..ROOMS {
+            let Some(slot) = (0..self.depth).find(|slot| self.rooms[room][*slot].is_some()) else {
+                continue;
+            };
+            if self.is_settled(room, slot) {
+                continue;
+            }
+            let amphipod = self.rooms[room][slot].unwrap();
+            let from = Location::Room { room, slot };
+
+            if let Some(to) = self.entry(amphipod) {
+                if self.hallway_clear(door(room), door(amphipod.room())) {
+                    return vec![Move { amphipod, from, to }];
+                }
+            }
+
+            moves.extend((0..HALLWAY_LEN)
+                .filter(|x| !(0..ROOMS).any(|r| door(r) == *x) && self.hallway_clear(door(room), *x))
+                .map(|x| Move { amphipod, from, to: Location::Hallway(x) }));
+        }
+        moves
+    }
+
+    // Energy needed if amphipods could pass through each other and rooms had a
+    // single slot, which never overestimates the actual cost
+    fn min_energy(&self) -> usize {
+        let mut energy = 0;
+        for (x, amphipod) in self.hallway.iter().enumerate() {
+            if let Some(amphipod) = amphipod {
+                energy += (x.abs_diff(door(amphipod.room())) + 1) * amphipod.energy();
+            }
+        }
+        for room in 0..ROOMS {
+            for slot in 0..self.depth {
+                let Some(amphipod) = self.rooms[room][slot] else { continue };
+                let steps = if amphipod.room() != room {
+                    slot + 1 + door(room).abs_diff(door(amphipod.room())) + 1
+                } else if !self.is_settled(room, slot) {
+                    // Has to step aside to let others out, and come back
+                    slot + 1 + 2 + 1
+                } else {
+                    0
+                };
+                energy += steps * amphipod.energy();
+            }
+        }
+        energy
+    }
+
+    /// Cheapest way to move all amphipods into their own rooms, if there is one
+    pub fn organize(&self) -> Option<Plan> {
+        let path = graph::astar(
+            *self,
+            |burrow| burrow.moves().into_iter().map(|m| (burrow.apply(&m), m.energy())).collect::<Vec<_>>(),
+            Burrow::min_energy,
+            Burrow::is_organized)?;
+
+        let moves = path.nodes
+            .windows(2)
+            .map(|pair| *pair[0]
+                .moves()
+                .iter()
+                .find(|m| pair[0].apply(m) == pair[1])
+                .expect("Consecutive nodes are connected by a move"))
+            .collect();
+        Some(Plan { moves, energy: path.cost })
+    }
+}
+
+impl Plan {
+    /// States of the burrow after each move, starting with `start` itself
+    pub fn replay(&self, start: &Burrow) -> Vec<Burrow> {
+        let mut states = vec![*start];
+        for m in &self.moves {
+            states.push(states.last().unwrap().apply(m));
+        }
+        states
+    }
+}
+
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = |a: &Option<Amphipod>| a.map_or('.', |a| a.letter());
+        let fill = |template: &str, cells: &mut dyn Iterator<Item = char>| {
+            template.chars().map(|c| if c == '?' { cells.next().unwrap() } else { c }).collect::<String>()
+        };
+
+        writeln!(f, "{}", TOP_WALL)?;
+        writeln!(f, "{}", fill(HALLWAY_ROW, &mut self.hallway.iter().map(letter)))?;
+        for slot in 0..self.depth {
+            let template = if slot == 0 { FIRST_ROOM_ROW } else { ROOM_ROW };
+            writeln!(f, "{}", fill(template, &mut self.rooms.iter().map(|room| letter(&room[slot]))))?;
+        }
+        writeln!(f, "{}", BOTTOM_WALL)
+    }
+}
+
+// Matches a row of the diagram against its template, returning the contents of its spaces
+fn parse_row<'a>(source: &Source<'a>, line: &'a str, template: &str)
+    -> Result<Vec<Option<Amphipod>>, ParseError> {
+    let mut cells = Vec::new();
+    let mut expected = template.chars();
+    for (pos, c) in line.char_indices() {
+        let token = &line[pos..pos + c.len_utf8()];
+        match expected.next() {
+            Some('?') if c == '.' => cells.push(None),
+            Some('?') => cells.push(Some(Amphipod::from_char(c).ok_or_else(|| source.error(token, "amphipod or '.'"))?)),
+            Some(wall) if wall == c => {},
+            Some(_) => return Err(source.error(token, "burrow diagram")),
+            None => return Err(source.error(&line[pos..], "end of line")),
+        }
+    }
+
+    if expected.next().is_some() {
+        return Err(source.error(&line[line.len()..], "burrow diagram"));
+    }
+    Ok(cells)
+}
+
+#[aoc_generator(day23)]
+pub fn input_generator(input: &str) -> Result<Burrow, ParseError> {
+    let source = Source::new(DAY, input);
+    let lines = input.lines().collect::<Vec<&str>>();
+    if lines.len() < 4 || lines.len() > MAX_DEPTH + 3 {
+        return Err(source.error(input, "burrow with one to four room rows"));
+    }
+
+    parse_row(&source, lines[0], TOP_WALL)?;
+    let hallway = parse_row(&source, lines[1], HALLWAY_ROW)?;
+    let room_lines = &lines[2..lines.len() - 1];
+    parse_row(&source, lines[lines.len() - 1], BOTTOM_WALL)?;
+
+    let mut burrow = Burrow {
+        depth: room_lines.len(),
+        hallway: hallway.try_into().expect("Row has been matched against the template"),
+        rooms: [[None; MAX_DEPTH]; ROOMS],
+    };
+    for (slot, line) in room_lines.iter().enumerate() {
+        let template = if slot == 0 { FIRST_ROOM_ROW } else { ROOM_ROW };
+        for (room, amphipod) in parse_row(&source, line, template)?.into_iter().enumerate() {
+            burrow.rooms[room][slot] = amphipod;
+        }
+    }
+
+    let amphipods = burrow.hallway.iter().chain(burrow.rooms.iter().flatten()).flatten().collect::<Vec<_>>();
+    if Amphipod::ALL.iter().any(|a| amphipods.iter().filter(|b| **b == a).count() != burrow.depth) {
+        return Err(source.error(input, "one amphipod of each type per room row"));
+    }
+
+    Ok(burrow)
+}
+
+fn min_energy(burrow: &Burrow) -> Result<usize, SolveError> {
+    burrow.organize()
+        .map(|plan| plan.energy)
+        .ok_or_else(|| SolveError::new(DAY, "amphipods cannot be organized"))
+}
+
+#[aoc(day23, part1)]
+pub fn solve_part1(burrow: &Burrow) -> Result<usize, SolveError> {
+    min_energy(burrow)
+}
+
+#[aoc(day23, part2)]
+pub fn solve_part2(burrow: &Burrow) -> Result<usize, SolveError> {
+    let unfolded = burrow.unfold().ok_or_else(|| SolveError::new(DAY, "diagram is too deep to unfold"))?;
+    min_energy(&unfolded)
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = DAY;
+    type Input = Burrow;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub fn sample_str() -> String {
+        String::from("#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########")
+    }
+
+    #[test]
+    pub fn test_input_generator() {
+        let burrow = input_generator(sample_str().as_str()).unwrap();
+
+        assert_eq!(burrow.depth, 2);
+        assert_eq!(burrow.get(Location::Room { room: 1, slot: 1 }), Some(Amphipod::D));
+        assert_eq!(burrow.to_string(), sample_str() + "\n");
+    }
+
+    #[test]
+    pub fn test_input_generator_invalid() {
+        let error = input_generator("#############\n#...........#\n###B#C#B#D###\n  #A#D#E#A#\n  #########").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (4, 8, "amphipod or '.'"));
+
+        let error = input_generator("#############\n#...........#\n###B#C#B#D###\n  #A#D#C#B#\n  #########").unwrap_err();
+        assert_eq!((error.line, error.expected), (1, "one amphipod of each type per room row"));
+    }
+
+    #[test]
+    pub fn test_unfold() {
+        let unfolded = input_generator(sample_str().as_str()).unwrap().unfold().unwrap();
+
+        assert_eq!(unfolded.to_string(), "#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########
+");
+        assert_eq!(unfolded.unfold(), None);
+    }
+
+    #[test]
+    pub fn test_solve_unsolvable() {
+        // Both amphipods in the hallway block each other's way home
+        let deadlocked = input_generator("#############\n#.....D.A...#\n###.#B#C#.###\n  #########").unwrap();
+        let unfolded = input_generator(sample_str().as_str()).unwrap().unfold().unwrap();
+
+        assert_eq!(solve_part1(&deadlocked).unwrap_err().to_string(), "day 23: amphipods cannot be organized");
+        assert_eq!(solve_part2(&unfolded).unwrap_err().to_string(), "day 23: diagram is too deep to unfold");
+    }
+
+    #[test]
+    pub fn test_moves() {
+        let burrow = input_generator(sample_str().as_str()).unwrap();
+
+        let moves = burrow.moves();
+
+        // Seven hallway spaces that are not right outside a room, for each room
+        assert_eq!(moves.len(), 28);
+        assert!(moves.iter().all(|m| matches!(m.from, Location::Room { slot: 0, .. })));
+        assert!(moves.iter().all(|m| !matches!(m.to, Location::Hallway(2 | 4 | 6 | 8))));
+    }
+
+    #[test]
+    pub fn test_organize_replay() {
+        let burrow = input_generator(sample_str().as_str()).unwrap();
+
+        let plan = burrow.organize().unwrap();
+        let states = plan.replay(&burrow);
+
+        assert_eq!(plan.energy, 12521);
+        assert_eq!(plan.moves.iter().map(Move::energy).sum::<usize>(), plan.energy);
+        assert!(states.last().unwrap().is_organized());
+        assert!(states.iter().all(|state| input_generator(&state.to_string()) == Ok(*state)));
+    }
+}
@@ -0,0 +1,415 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use crate::error::{ParseError, SolveError, Source};
+use crate::solution::Solution;
+
+const DAY: u8 = 24;
+const MODEL_NUMBER_LEN: usize = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(Register),
+    Binary(Operation, Register, Operand),
+}
+
+impl Register {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Register, ParseError> {
+        match s {
+            "w" => Ok(Register::W),
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            _ => Err(source.error(s, "register w, x, y or z")),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Register::W => "w",
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        }
+    }
+}
+
+impl Operand {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Operand, ParseError> {
+        match Register::parse(source, s) {
+            Ok(register) => Ok(Operand::Register(register)),
+            Err(_) => Ok(Operand::Number(source.parse::<i64>(s, "register or number")?)),
+        }
+    }
+}
+
+impl Operation {
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Add => "add",
+            Operation::Mul => "mul",
+            Operation::Div => "div",
+            Operation::Mod => "mod",
+            Operation::Eql => "eql",
+        }
+    }
+}
+
+impl Instruction {
+    fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Instruction, ParseError> {
+        let (name, args) = source.split_once(s, " ", "' ' followed by register")?;
+        let operation = match name {
+            "inp" => return Ok(Instruction::Inp(Register::parse(source, args)?)),
+            "add" => Operation::Add,
+            "mul" => Operation::Mul,
+            "div" => Operation::Div,
+            "mod" => Operation::Mod,
+            "eql" => Operation::Eql,
+            _ => return Err(source.error(name, "inp, add, mul, div, mod or eql")),
+        };
+
+        let (register_str, operand_str) = source.split_once(args, " ", "' ' followed by operand")?;
+        Ok(Instruction::Binary(operation, Register::parse(source, register_str)?, Operand::parse(source, operand_str)?))
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Instruction::parse(&Source::new(DAY, s), s)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Inp(register) => write!(f, "inp {}", register.name()),
+            Instruction::Binary(operation, register, Operand::Register(other)) =>
+                write!(f, "{} {} {}", operation.name(), register.name(), other.name()),
+            Instruction::Binary(operation, register, Operand::Number(n)) =>
+                write!(f, "{} {} {}", operation.name(), register.name(), n),
+        }
+    }
+}
+
+/// Reason for the ALU to stop, along with the index of the failing instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionError {
+    MissingInput(usize),
+    DivisionByZero(usize),
+    InvalidModulo(usize),
+    Overflow(usize),
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecutionError::MissingInput(i) => write!(f, "Instruction {} reads past the end of the input", i + 1),
+            ExecutionError::DivisionByZero(i) => write!(f, "Instruction {} divides by zero", i + 1),
+            ExecutionError::InvalidModulo(i) => write!(f, "Instruction {} takes a negative modulo", i + 1),
+            ExecutionError::Overflow(i) => write!(f, "Instruction {} overflows", i + 1),
+        }
+    }
+}
+
+impl Error for ExecutionError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register as usize]
+    }
+
+    /// Runs a program to its end, consuming one value of `input` per `inp` instruction
+    pub fn execute(&mut self, program: &[Instruction], input: &[i64]) -> Result<(), ExecutionError> {
+        let mut input = input.iter();
+        for (i, instruction) in program.iter().enumerate() {
+            match *instruction {
+                Instruction::Inp(register) => {
+                    self.registers[register as usize] = *input.next().ok_or(ExecutionError::MissingInput(i))?;
+                },
+                Instruction::Binary(operation, register, operand) => {
+                    let a = self.get(register);
+                    let b = match operand {
+                        Operand::Register(other) => self.get(other),
+                        Operand::Number(n) => n,
+                    };
+                    let result = match operation {
+                        Operation::Add => a.checked_add(b).ok_or(ExecutionError::Overflow(i))?,
+                        Operation::Mul => a.checked_mul(b).ok_or(ExecutionError::Overflow(i))?,
+                        Operation::Div if b == 0 => return Err(ExecutionError::DivisionByZero(i)),
+                        Operation::Div => a.checked_div(b).ok_or(ExecutionError::Overflow(i))?,
+                        Operation::Mod if a < 0 || b <= 0 => return Err(ExecutionError::InvalidModulo(i)),
+                        Operation::Mod => a % b,
+                        Operation::Eql => (a == b) as i64,
+                    };
+                    self.registers[register as usize] = result;
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs a program on a freshly reset ALU
+pub fn run(program: &[Instruction], input: &[i64]) -> Result<Alu, ExecutionError> {
+    let mut alu = Alu::default();
+    alu.execute(program, input)?;
+    Ok(alu)
+}
+
+/// Constants that tell the blocks of MONAD apart, each of which reads one digit.
+///
+/// A block with a divisor of 1 pushes `digit + offset` onto a stack of base-26
+/// digits kept in z. A block with a divisor of 26 pops the top of that stack,
+/// and pushes its own value back unless `digit` equals the popped value plus
+/// `check`. Thus z is 0 at the end only if every such comparison succeeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    pub divisor: i64,
+    pub check: i64,
+    pub offset: i64,
+}
+
+impl Block {
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let text = format!("inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+            mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\nmul y x\nadd z y",
+            self.divisor, self.check, self.offset);
+        input_generator(&text).expect("Block template is valid")
+    }
+}
+
+fn number_operand(instruction: &Instruction) -> Option<i64> {
+    match instruction {
+        Instruction::Binary(_, _, Operand::Number(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+/// Splits a program into MONAD blocks, unless it has a different structure
+pub fn blocks(program: &[Instruction]) -> Option<Vec<Block>> {
+    let len = Block { divisor: 1, check: 0, offset: 0 }.instructions().len();
+    if !program.len().is_multiple_of(len) {
+        return None;
+    }
+
+    program
+        .chunks(len)
+        .map(|chunk| {
+            let block = Block {
+                divisor: number_operand(&chunk[4])?,
+                check: number_operand(&chunk[5])?,
+                offset: number_operand(&chunk[15])?,
+            };
+            (block.instructions() == chunk).then_some(block)
+        })
+        .collect()
+}
+
+/// Whether MONAD reports the digits to be a valid model number
+pub fn accepts(program: &[Instruction], digits: &[i64]) -> bool {
+    run(program, digits).is_ok_and(|alu| alu.get(Register::Z) == 0)
+}
+
+/// Digits of the largest or smallest model number that passes all comparisons
+/// of the blocks, by pairing each popping block with the block that pushed
+fn choose_digits(blocks: &[Block], largest: bool) -> Option<Vec<i64>> {
+    let mut digits = vec![0; blocks.len()];
+    let mut stack = Vec::new();
+
+    for (j, block) in blocks.iter().enumerate() {
+        match block.divisor {
+            // Comparisons of pushing blocks can never succeed
+            1 if block.check > 9 => stack.push(j),
+            26 => {
+                let i = stack.pop()?;
+                let difference = blocks[i].offset + block.check;
+                digits[i] = if largest { 9.min(9 - difference) } else { 1.max(1 - difference) };
+                digits[j] = digits[i] + difference;
+                if !(1..=9).contains(&digits[i]) || !(1..=9).contains(&digits[j]) {
+                    return None;
+                }
+            },
+            _ => return None,
+        }
+    }
+
+    stack.is_empty().then_some(digits)
+}
+
+/// Largest or smallest model number accepted by MONAD. The digits found by
+/// analysing its blocks are checked by actually running the program.
+pub fn model_number(program: &[Instruction], largest: bool) -> Option<u64> {
+    let digits = choose_digits(&blocks(program)?, largest)?;
+    if !accepts(program, &digits) {
+        return None;
+    }
+    Some(digits.iter().fold(0, |number, digit| number * 10 + *digit as u64))
+}
+
+#[aoc_generator(day24)]
+pub fn input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let source = Source::new(DAY, input);
+    input
+        .lines()
+        .map(|line| Instruction::parse(&source, line))
+        .collect::<Result<Vec<Instruction>, ParseError>>()
+}
+
+fn monad_model_number(program: &[Instruction], largest: bool) -> Result<u64, SolveError> {
+    if program.iter().filter(|i| matches!(i, Instruction::Inp(_))).count() != MODEL_NUMBER_LEN {
+        return Err(SolveError::new(DAY, format!("MONAD has to read {} digits", MODEL_NUMBER_LEN)));
+    }
+    model_number(program, largest).ok_or_else(|| SolveError::new(DAY, "no model number is accepted"))
+}
+
+#[aoc(day24, part1)]
+pub fn solve_part1(program: &[Instruction]) -> Result<u64, SolveError> {
+    monad_model_number(program, true)
+}
+
+#[aoc(day24, part2)]
+pub fn solve_part2(program: &[Instruction]) -> Result<u64, SolveError> {
+    monad_model_number(program, false)
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Instruction>;
+    type Output = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn binary_str() -> String {
+        String::from("inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2")
+    }
+
+    // Four digits where the last two have to be one and three below the middle and first one
+    fn small_monad() -> Vec<Instruction> {
+        [(1, 12, 4), (1, 11, 2), (26, -3, 9), (26, -7, 1)]
+            .iter()
+            .flat_map(|(divisor, check, offset)| Block { divisor: *divisor, check: *check, offset: *offset }.instructions())
+            .collect()
+    }
+
+    #[test]
+    pub fn test_input_generator() {
+        let program = input_generator("inp x\nmul x -1\neql z x").unwrap();
+
+        assert_eq!(program[1], Instruction::Binary(Operation::Mul, Register::X, Operand::Number(-1)));
+        assert_eq!(program[2], Instruction::Binary(Operation::Eql, Register::Z, Operand::Register(Register::X)));
+        assert_eq!(program.iter().map(|i| i.to_string()).collect::<Vec<String>>(), ["inp x", "mul x -1", "eql z x"]);
+
+        let error = input_generator("inp x\nsub x 1").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (2, 1, "inp, add, mul, div, mod or eql"));
+    }
+
+    #[test]
+    pub fn test_run() {
+        let negate = input_generator("inp x\nmul x -1").unwrap();
+        assert_eq!(run(&negate, &[5]).unwrap().get(Register::X), -5);
+
+        let triple = input_generator("inp z\ninp x\nmul z 3\neql z x").unwrap();
+        assert_eq!(run(&triple, &[2, 6]).unwrap().get(Register::Z), 1);
+        assert_eq!(run(&triple, &[2, 5]).unwrap().get(Register::Z), 0);
+
+        let binary = input_generator(binary_str().as_str()).unwrap();
+        let alu = run(&binary, &[13]).unwrap();
+        assert_eq!([Register::W, Register::X, Register::Y, Register::Z].map(|r| alu.get(r)), [1, 1, 0, 1]);
+    }
+
+    #[test]
+    pub fn test_run_errors() {
+        let program = input_generator("inp x\ninp y\nmod x y\ndiv y x").unwrap();
+
+        assert_eq!(run(&program, &[1]), Err(ExecutionError::MissingInput(1)));
+        assert_eq!(run(&program, &[1, 0]), Err(ExecutionError::InvalidModulo(2)));
+        assert_eq!(run(&program, &[0, 1]), Err(ExecutionError::DivisionByZero(3)));
+    }
+
+    #[test]
+    pub fn test_blocks() {
+        let program = small_monad();
+
+        assert_eq!(blocks(&program).unwrap()[2], Block { divisor: 26, check: -3, offset: 9 });
+        assert_eq!(blocks(&program[1..]), None);
+    }
+
+    #[test]
+    pub fn test_model_number_matches_brute_force() {
+        let program = small_monad();
+        let accepted = (1111..=9999)
+            .filter(|n: &u64| {
+                let digits = n.to_string().chars().map(|c| c.to_digit(10).unwrap() as i64).collect::<Vec<i64>>();
+                !digits.contains(&0) && accepts(&program, &digits)
+            })
+            .collect::<Vec<u64>>();
+
+        assert_eq!(model_number(&program, true), accepted.last().copied());
+        assert_eq!(model_number(&program, false), accepted.first().copied());
+        assert_eq!(model_number(&program, true), Some(9986));
+    }
+
+    #[test]
+    pub fn test_solve_not_monad() {
+        let inputs_only = input_generator(&"inp w\n".repeat(MODEL_NUMBER_LEN)).unwrap();
+
+        assert_eq!(solve_part1(&small_monad()).unwrap_err().to_string(), "day 24: MONAD has to read 14 digits");
+        assert_eq!(solve_part2(&inputs_only).unwrap_err().to_string(), "day 24: no model number is accepted");
+    }
+}
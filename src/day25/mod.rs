@@ -0,0 +1,177 @@
+use crate::error::{ParseError, SolveError, Source};
+use crate::grid::Grid;
+use crate::solution::{Part, Solution};
+
+const DAY: u8 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Cell {
+    fn from_char(c: char) -> Option<Cell> {
+        match c {
+            '.' => Some(Cell::Empty),
+            '>' => Some(Cell::East),
+            'v' => Some(Cell::South),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::East => '>',
+            Cell::South => 'v',
+        }
+    }
+}
+
+/// Map of sea cucumbers, which wraps around at its edges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeaFloor {
+    pub cells: Grid<Cell>,
+}
+
+impl SeaFloor {
+    // All cucumbers of a herd that face an empty cell move at the same time
+    fn move_herd(&self, herd: Cell) -> (SeaFloor, usize) {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let mut cells = self.cells.clone();
+        let mut moved = 0;
+
+        for ((x, y), cell) in self.cells.iter() {
+            if *cell != herd {
+                continue;
+            }
+            let target = match herd {
+                Cell::East => ((x + 1) % width, y),
+                _ => (x, (y + 1) % height),
+            };
+            if self.cells[target] == Cell::Empty {
+                cells[target] = herd;
+                cells[(x, y)] = Cell::Empty;
+                moved += 1;
+            }
+        }
+
+        (SeaFloor { cells }, moved)
+    }
+
+    /// Moves the east-facing herd and then the south-facing one, returning the
+    /// new map along with the number of cucumbers that moved
+    pub fn step(&self) -> (SeaFloor, usize) {
+        let (east_moved, east) = self.move_herd(Cell::East);
+        let (south_moved, south) = east_moved.move_herd(Cell::South);
+
+        (south_moved, east + south)
+    }
+
+    /// Number of the first step in which no sea cucumber moves, or `None` if
+    /// the cucumbers end up moving around in circles forever
+    pub fn steps_until_still(&self) -> Option<usize> {
+        // Brent's cycle detection: only one earlier map is kept, which moves
+        // up whenever the steps since it reach the next power of two
+        let mut floor = self.clone();
+        let mut checkpoint = self.clone();
+        let (mut since_checkpoint, mut power) = (0, 1);
+        let mut steps = 1;
+        loop {
+            let (next, moved) = floor.step();
+            if moved == 0 {
+                return Some(steps);
+            }
+            if next == checkpoint {
+                return None;
+            }
+
+            since_checkpoint += 1;
+            if since_checkpoint == power {
+                checkpoint = next.clone();
+                since_checkpoint = 0;
+                power *= 2;
+            }
+            floor = next;
+            steps += 1;
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.cells.render(|cell| cell.to_char())
+    }
+}
+
+#[aoc_generator(day25)]
+pub fn input_generator(input: &str) -> Result<SeaFloor, ParseError> {
+    let cells = Grid::parse_chars(&Source::new(DAY, input), input, "'>', 'v' or '.'", Cell::from_char)?;
+
+    Ok(SeaFloor { cells })
+}
+
+#[aoc(day25, part1)]
+pub fn solve_part1(floor: &SeaFloor) -> Result<usize, SolveError> {
+    floor.steps_until_still().ok_or_else(|| SolveError::new(DAY, "sea cucumbers never stop moving"))
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = DAY;
+    const PARTS: &'static [Part] = &[Part::One];
+    type Input = SeaFloor;
+    type Output = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError> {
+        solve_part1(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_step_single_row() {
+        let floor = input_generator("...>>>>>...").unwrap();
+
+        let (once, moved) = floor.step();
+        let (twice, _) = once.step();
+
+        assert_eq!(moved, 1);
+        assert_eq!(once.render(), "...>>>>.>..\n");
+        assert_eq!(twice.render(), "...>>>.>.>.\n");
+    }
+
+    #[test]
+    pub fn test_step_wraps_around() {
+        let floor = input_generator("..........\n.>v....v..\n.......>..\n..........").unwrap();
+
+        let (next, moved) = floor.step();
+
+        assert_eq!(moved, 3);
+        assert_eq!(next.render(), "..........\n.>........\n..v....v>.\n..........\n");
+    }
+
+    #[test]
+    pub fn test_steps_until_still_never() {
+        let floor = input_generator(">.").unwrap();
+
+        assert_eq!(floor.steps_until_still(), None);
+        assert_eq!(input_generator("v..>.....\n.........\n.........").unwrap().steps_until_still(), None);
+        assert_eq!(input_generator(">>\nv.").unwrap().steps_until_still(), Some(1));
+    }
+
+    #[test]
+    pub fn test_input_generator_invalid() {
+        let error = input_generator("..>\n.<.").unwrap_err();
+
+        assert_eq!((error.line, error.column, error.expected), (2, 2, "'>', 'v' or '.'"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error returned by all input generators, pointing at the offending token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}, line {}, column {}: expected {}, ",
+            self.day, self.line, self.column, self.expected)?;
+
+        if self.token.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found {:?}", self.token)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error returned by solvers for inputs that parse, but have no answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub day: u8,
+    pub reason: String,
+}
+
+impl SolveError {
+    pub fn new(day: u8, reason: impl Into<String>) -> SolveError {
+        SolveError { day, reason: reason.into() }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}: {}", self.day, self.reason)
+    }
+}
+
+impl Error for SolveError {}
+
+/// Puzzle input of a given day, used to locate tokens when reporting errors
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+    day: u8,
+    text: &'a str,
+    first_line: usize,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(day: u8, text: &'a str) -> Source<'a> {
+        Source::at_line(day, text, 1)
+    }
+
+    /// Source for text that starts at line `first_line` of the puzzle input,
+    /// e.g. a single line of an input that is read one line at a time
+    pub fn at_line(day: u8, text: &'a str, first_line: usize) -> Source<'a> {
+        Source { day, text, first_line }
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Builds an error for `token`, which has to be a slice of the source text
+    /// for line and column to be meaningful. An empty slice at the end of a line
+    /// reports that something is missing there.
+    pub fn error(&self, token: &str, expected: &'static str) -> ParseError {
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|offset| *offset <= self.text.len())
+            .unwrap_or(0);
+
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + self.first_line;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        ParseError { day: self.day, line, column, token: token.to_owned(), expected }
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &'static str) -> Result<T, ParseError> {
+        token.parse::<T>().map_err(|_| self.error(token, expected))
+    }
+
+    pub fn split_once(&self, s: &'a str, delimiter: &str, expected: &'static str)
+        -> Result<(&'a str, &'a str), ParseError> {
+        s.split_once(delimiter).ok_or_else(|| self.error(&s[s.len()..], expected))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_error_location() {
+        let text = "1,2\n3,x4\n";
+        let source = Source::new(6, text);
+
+        let error = source.parse::<u8>(&text[6..8], "number").unwrap_err();
+
+        assert_eq!(error, ParseError {
+            day: 6, line: 2, column: 3, token: String::from("x4"), expected: "number"
+        });
+        assert_eq!(error.to_string(), "day 6, line 2, column 3: expected number, found \"x4\"");
+    }
+
+    #[test]
+    pub fn test_error_at_line() {
+        let text = "3,x4";
+        let source = Source::at_line(6, text, 2);
+
+        let error = source.parse::<u8>(&text[2..], "number").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
+    #[test]
+    pub fn test_error_missing_token() {
+        let text = "ab\nfoo";
+        let source = Source::new(12, text);
+
+        let error = source.split_once(&text[3..], "-", "'-' separator").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.to_string(), "day 12, line 2, column 4: expected '-' separator, found nothing");
+    }
+}
@@ -0,0 +1,443 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::{Add, Neg, RangeInclusive, Sub};
+
+use crate::error::{ParseError, Source};
+
+/// Point on an integer plane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// Parses `x,y`
+    pub fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Point, ParseError> {
+        let (x_str, y_str) = source.split_once(s, ",", "',' followed by y coordinate")?;
+        let x = source.parse::<i64>(x_str, "x coordinate")?;
+        let y = source.parse::<i64>(y_str, "y coordinate")?;
+
+        Ok(Point { x, y })
+    }
+}
+
+/// Straight line between two points, both of which are part of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Segment {
+    pub fn new(start: Point, end: Point) -> Segment {
+        Segment { start, end }
+    }
+
+    /// Parses `x1,y1 -> x2,y2`
+    pub fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Segment, ParseError> {
+        let (start_str, end_str) = source.split_once(s, " -> ", "' -> ' followed by end point")?;
+        let start = Point::parse(source, start_str)?;
+        let end = Point::parse(source, end_str)?;
+
+        Ok(Segment { start, end })
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    /// Whether the segment runs at exactly 45 degrees
+    pub fn is_diagonal(&self) -> bool {
+        !self.is_horizontal() && self.start.x.abs_diff(self.end.x) == self.start.y.abs_diff(self.end.y)
+    }
+
+    /// All points on the segment from start to end, provided it is horizontal,
+    /// vertical or diagonal, since other lines do not hit integer points evenly
+    pub fn rasterise(&self) -> Option<impl Iterator<Item = Point>> {
+        if !(self.is_horizontal() || self.is_vertical() || self.is_diagonal()) {
+            return None;
+        }
+
+        let start = self.start;
+        let (dx, dy) = (self.end.x.cmp(&start.x), self.end.y.cmp(&start.y));
+        let length = start.x.abs_diff(self.end.x).max(start.y.abs_diff(self.end.y));
+
+        Some((0..=length).map(move |i| Point::new(offset(start.x, dx, i), offset(start.y, dy, i))))
+    }
+}
+
+// Coordinate `steps` away from `from` in the given direction, which fits into
+// i64 as long as it does not go past the end of a segment
+fn offset(from: i64, direction: Ordering, steps: u64) -> i64 {
+    match direction {
+        Ordering::Less => from.checked_sub_unsigned(steps),
+        Ordering::Equal => Some(from),
+        Ordering::Greater => from.checked_add_unsigned(steps),
+    }
+    .expect("Points of a segment lie between its start and end")
+}
+
+/// Point or offset in integer space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub fn new(x: i64, y: i64, z: i64) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    /// Parses `x,y,z`
+    pub fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Vec3, ParseError> {
+        let (x_str, rest) = source.split_once(s, ",", "',' followed by y coordinate")?;
+        let (y_str, z_str) = source.split_once(rest, ",", "',' followed by z coordinate")?;
+        let x = source.parse::<i64>(x_str, "x coordinate")?;
+        let y = source.parse::<i64>(y_str, "y coordinate")?;
+        let z = source.parse::<i64>(z_str, "z coordinate")?;
+
+        Ok(Vec3 { x, y, z })
+    }
+
+    fn to_array(self) -> [i64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn manhattan(&self, other: &Vec3) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn squared_length(&self) -> i64 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// One of the 24 rotations that map the coordinate axes onto coordinate axes,
+/// as a matrix with a single 1 or -1 per row and column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    matrix: [[i64; 3]; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation { matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]] };
+
+    /// All 24 rotations, starting with the identity
+    pub fn all() -> Vec<Rotation> {
+        let permutations = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let mut rotations = Vec::with_capacity(24);
+        for axes in permutations {
+            for signs in 0..8 {
+                let mut matrix = [[0; 3]; 3];
+                for row in 0..3 {
+                    matrix[row][axes[row]] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+
+                // The other half of the signed permutations are reflections
+                let rotation = Rotation { matrix };
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+        rotations
+    }
+
+    fn determinant(&self) -> i64 {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        let v = v.to_array();
+        let [x, y, z] = self.matrix.map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum());
+        Vec3::new(x, y, z)
+    }
+
+    /// Rotation that applies `other` first and `self` second
+    pub fn then_after(&self, other: &Rotation) -> Rotation {
+        let mut matrix = [[0; 3]; 3];
+        for (row, out) in matrix.iter_mut().enumerate() {
+            for (col, value) in out.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.matrix[row][k] * other.matrix[k][col]).sum();
+            }
+        }
+        Rotation { matrix }
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        let mut matrix = [[0; 3]; 3];
+        for (row, out) in matrix.iter_mut().enumerate() {
+            for (col, value) in out.iter_mut().enumerate() {
+                *value = self.matrix[col][row];
+            }
+        }
+        Rotation { matrix }
+    }
+}
+
+/// Parses a range like `x=-5..10`, given its `prefix` such as `x=`
+pub fn parse_range<'a>(source: &Source<'a>, s: &'a str, prefix: &'static str)
+    -> Result<RangeInclusive<i64>, ParseError> {
+    let range_str = s.strip_prefix(prefix).ok_or_else(|| source.error(s, prefix))?;
+    let (start_str, end_str) = source.split_once(range_str, "..", "'..' followed by range end")?;
+    let start = source.parse::<i64>(start_str, "range start")?;
+    let end = source.parse::<i64>(end_str, "range end")?;
+
+    if start > end {
+        return Err(source.error(range_str, "range start not above range end"));
+    }
+
+    Ok(start..=end)
+}
+
+/// Box of integer cubes with faces parallel to the axes, including both corners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Cuboid {
+    pub fn new(min: Vec3, max: Vec3) -> Cuboid {
+        Cuboid { min, max }
+    }
+
+    /// Parses `x=x1..x2,y=y1..y2,z=z1..z2`
+    pub fn parse<'a>(source: &Source<'a>, s: &'a str) -> Result<Cuboid, ParseError> {
+        let (x_str, rest) = source.split_once(s, ",", "',' followed by y range")?;
+        let (y_str, z_str) = source.split_once(rest, ",", "',' followed by z range")?;
+        let x = parse_range(source, x_str, "x=")?;
+        let y = parse_range(source, y_str, "y=")?;
+        let z = parse_range(source, z_str, "z=")?;
+
+        Ok(Cuboid {
+            min: Vec3::new(*x.start(), *y.start(), *z.start()),
+            max: Vec3::new(*x.end(), *y.end(), *z.end()),
+        })
+    }
+
+    /// Number of cubes within the cuboid, or `None` if it does not fit in an
+    /// i128, which takes sides spanning most of the i64 range
+    pub fn volume(&self) -> Option<i128> {
+        let side = |min: i64, max: i64| max as i128 - min as i128 + 1;
+        side(self.min.x, self.max.x)
+            .checked_mul(side(self.min.y, self.max.y))?
+            .checked_mul(side(self.min.z, self.max.z))
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = Vec3::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z));
+        let max = Vec3::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z));
+
+        if min.x <= max.x && min.y <= max.y && min.z <= max.z {
+            Some(Cuboid { min, max })
+        } else {
+            None
+        }
+    }
+}
+
+/// Set of cubes built by adding and removing cuboids, kept as a sum of cuboids
+/// with signed multiplicities following the inclusion-exclusion principle
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CuboidSet {
+    terms: HashMap<Cuboid, i64>,
+}
+
+impl CuboidSet {
+    pub fn new() -> CuboidSet {
+        CuboidSet::default()
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid) {
+        self.remove(cuboid);
+        *self.terms.entry(cuboid).or_insert(0) += 1;
+    }
+
+    pub fn remove(&mut self, cuboid: Cuboid) {
+        // Cancel out every term where it overlaps with the removed cuboid
+        let overlaps = self.terms
+            .iter()
+            .filter_map(|(term, count)| term.intersection(&cuboid).map(|overlap| (overlap, *count)))
+            .collect::<Vec<(Cuboid, i64)>>();
+
+        for (overlap, count) in overlaps {
+            *self.terms.entry(overlap).or_insert(0) -= count;
+        }
+        self.terms.retain(|_, count| *count != 0);
+    }
+
+    /// Number of cubes in the set, or `None` if the volume of a term or the
+    /// sum of all terms does not fit in an i128
+    pub fn volume(&self) -> Option<i128> {
+        self.terms
+            .iter()
+            .try_fold(0i128, |volume, (term, count)| volume.checked_add(term.volume()?.checked_mul(*count as i128)?))
+    }
+
+    /// The cubes of the set that lie within `region`
+    pub fn clip(&self, region: &Cuboid) -> CuboidSet {
+        let mut clipped = CuboidSet::new();
+        for (term, count) in &self.terms {
+            if let Some(overlap) = term.intersection(region) {
+                *clipped.terms.entry(overlap).or_insert(0) += count;
+            }
+        }
+        clipped.terms.retain(|_, count| *count != 0);
+        clipped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn segment(s: &str) -> Segment {
+        Segment::parse(&Source::new(0, s), s).unwrap()
+    }
+
+    fn points(coords: &[(i64, i64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    pub fn test_parse() {
+        assert_eq!(segment("0,9 -> 5,9"), Segment::new(Point::new(0, 9), Point::new(5, 9)));
+
+        let text = "0,9 -> 5;9";
+        let error = Segment::parse(&Source::new(5, text), text).unwrap_err();
+        assert_eq!((error.column, error.expected), (11, "',' followed by y coordinate"));
+    }
+
+    #[test]
+    pub fn test_rasterise() {
+        assert_eq!(segment("1,1 -> 1,3").rasterise().unwrap().collect::<Vec<Point>>(),
+            points(&[(1, 1), (1, 2), (1, 3)]));
+        assert_eq!(segment("9,7 -> 7,7").rasterise().unwrap().collect::<Vec<Point>>(),
+            points(&[(9, 7), (8, 7), (7, 7)]));
+        assert_eq!(segment("9,7 -> 7,9").rasterise().unwrap().collect::<Vec<Point>>(),
+            points(&[(9, 7), (8, 8), (7, 9)]));
+        assert_eq!(segment("4,4 -> 4,4").rasterise().unwrap().collect::<Vec<Point>>(),
+            points(&[(4, 4)]));
+        assert!(segment("0,0 -> 2,1").rasterise().is_none());
+    }
+
+    #[test]
+    pub fn test_rasterise_full_range() {
+        let (min, max) = (i64::MIN, i64::MAX);
+
+        assert_eq!(segment(&format!("{},0 -> {},0", min, max)).rasterise().unwrap().take(2).collect::<Vec<Point>>(),
+            points(&[(min, 0), (min + 1, 0)]));
+        assert_eq!(segment(&format!("{},{} -> {},{}", max, max, min, min)).rasterise().unwrap().nth(1),
+            Some(Point::new(max - 1, max - 1)));
+    }
+
+    #[test]
+    pub fn test_vec3_parse() {
+        let text = "-618,-824,-621";
+        assert_eq!(Vec3::parse(&Source::new(0, text), text).unwrap(), Vec3::new(-618, -824, -621));
+
+        let text = "1,2";
+        let error = Vec3::parse(&Source::new(0, text), text).unwrap_err();
+        assert_eq!((error.column, error.expected), (4, "',' followed by z coordinate"));
+    }
+
+    #[test]
+    pub fn test_rotations() {
+        let rotations = Rotation::all();
+        let v = Vec3::new(1, 2, 3);
+
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+        assert_eq!(HashSet::<Vec3>::from_iter(rotations.iter().map(|r| r.apply(v))).len(), 24);
+        for a in &rotations {
+            assert_eq!(a.inverse().then_after(a), Rotation::IDENTITY);
+            for b in &rotations {
+                assert!(rotations.contains(&a.then_after(b)));
+                assert_eq!(a.then_after(b).apply(v), a.apply(b.apply(v)));
+            }
+        }
+    }
+
+    fn cuboid(s: &str) -> Cuboid {
+        Cuboid::parse(&Source::new(0, s), s).unwrap()
+    }
+
+    #[test]
+    pub fn test_cuboid() {
+        let a = cuboid("x=10..12,y=10..12,z=10..12");
+        let b = cuboid("x=11..13,y=11..13,z=11..13");
+
+        assert_eq!(a, Cuboid::new(Vec3::new(10, 10, 10), Vec3::new(12, 12, 12)));
+        assert_eq!(a.volume(), Some(27));
+        assert_eq!(a.intersection(&b), Some(cuboid("x=11..12,y=11..12,z=11..12")));
+        assert_eq!(a.intersection(&cuboid("x=13..14,y=10..12,z=10..12")), None);
+    }
+
+    #[test]
+    pub fn test_cuboid_volume_large() {
+        let large = cuboid("x=-3000000..3000000,y=-3000000..3000000,z=-3000000..3000000");
+        let full = Cuboid::new(Vec3::new(i64::MIN, i64::MIN, 0), Vec3::new(i64::MAX, i64::MAX, 0));
+
+        assert_eq!(large.volume(), Some(6000001i128.pow(3)));
+        assert_eq!(full.volume(), None);
+        assert_eq!(Cuboid::new(Vec3::new(i64::MIN, 0, 0), Vec3::new(i64::MAX, 0, 0)).volume(), Some(1 << 64));
+    }
+
+    #[test]
+    pub fn test_cuboid_set() {
+        let mut set = CuboidSet::new();
+
+        set.insert(cuboid("x=10..12,y=10..12,z=10..12"));
+        assert_eq!(set.volume(), Some(27));
+        set.insert(cuboid("x=11..13,y=11..13,z=11..13"));
+        assert_eq!(set.volume(), Some(46));
+        set.remove(cuboid("x=9..11,y=9..11,z=9..11"));
+        assert_eq!(set.volume(), Some(38));
+        set.insert(cuboid("x=10..10,y=10..10,z=10..10"));
+        assert_eq!(set.volume(), Some(39));
+
+        assert_eq!(set.clip(&cuboid("x=12..20,y=12..20,z=12..20")).volume(), Some(8));
+        set.remove(cuboid("x=0..20,y=0..20,z=0..20"));
+        assert_eq!(set, CuboidSet::new());
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use priority_queue::DoublePriorityQueue;
+
+/// Path found by a search, listing all nodes from start to target inclusively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+fn reconstruct<N: Clone + Eq + Hash>(prev: &HashMap<N, N>, target: N) -> Vec<N> {
+    let mut nodes = vec![target];
+    while let Some(node) = prev.get(nodes.last().unwrap()) {
+        nodes.push(node.clone());
+    }
+    nodes.reverse();
+    nodes
+}
+
+/// Cheapest path from `start` to the first node satisfying `is_target`, where
+/// `successors` yields the neighbors of a node along with the cost to reach
+/// them. Returns `None` if no target is reachable.
+pub fn dijkstra<N, C, FS, I, FT>(start: N, successors: FS, is_target: FT) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FS: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    FT: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::default(), is_target)
+}
+
+/// Like `dijkstra`, but explores nodes in order of their cost so far plus
+/// `heuristic`, which must never overestimate the remaining cost to a target.
+/// The heuristic does not have to be consistent, as nodes that were already
+/// explored are explored again once a cheaper path to them turns up.
+pub fn astar<N, C, FS, I, FH, FT>(start: N, mut successors: FS, mut heuristic: FH, mut is_target: FT)
+    -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FS: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FT: FnMut(&N) -> bool,
+{
+    let mut dist: HashMap<N, C> = HashMap::new();
+    let mut prev: HashMap<N, N> = HashMap::new();
+
+    let mut queue = DoublePriorityQueue::<N, C>::new();
+    dist.insert(start.clone(), C::default());
+    queue.push(start.clone(), heuristic(&start));
+
+    while let Some((u, _)) = queue.pop_min() {
+        let dist_u = dist[&u];
+
+        if is_target(&u) {
+            return Some(Path { nodes: reconstruct(&prev, u), cost: dist_u });
+        }
+
+        for (v, cost) in successors(&u) {
+            let alt = dist_u + cost;
+            if dist.get(&v).is_none_or(|d| alt < *d) {
+                dist.insert(v.clone(), alt);
+                prev.insert(v.clone(), u.clone());
+                let priority = alt + heuristic(&v);
+                queue.push(v, priority);
+            }
+        }
+    }
+
+    None
+}
+
+/// Path with the fewest edges from `start` to the first node satisfying `is_target`
+pub fn bfs<N, FS, I, FT>(start: N, mut successors: FS, mut is_target: FT) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    FS: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    FT: FnMut(&N) -> bool,
+{
+    let mut prev: HashMap<N, N> = HashMap::new();
+    let mut seen: HashSet<N> = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(u) = queue.pop_front() {
+        if is_target(&u) {
+            return Some(reconstruct(&prev, u));
+        }
+
+        for v in successors(&u) {
+            if seen.insert(v.clone()) {
+                prev.insert(v.clone(), u.clone());
+                queue.push_back(v);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // a -1-> b -1-> c -1-> d, with a more expensive shortcut a -5-> d
+    fn edges(node: &&'static str) -> Vec<(&'static str, u32)> {
+        match *node {
+            "a" => vec![("b", 1), ("d", 5)],
+            "b" => vec![("c", 1)],
+            "c" => vec![("d", 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    pub fn test_dijkstra() {
+        let path = dijkstra("a", edges, |n| *n == "d").unwrap();
+
+        assert_eq!(path, Path { nodes: vec!["a", "b", "c", "d"], cost: 3 });
+    }
+
+    #[test]
+    pub fn test_dijkstra_unreachable() {
+        assert_eq!(dijkstra("b", edges, |n| *n == "a"), None);
+    }
+
+    #[test]
+    pub fn test_dijkstra_start_is_target() {
+        let path = dijkstra("a", edges, |n| *n == "a").unwrap();
+
+        assert_eq!(path, Path { nodes: vec!["a"], cost: 0 });
+    }
+
+    #[test]
+    pub fn test_astar_grid() {
+        // 5x5 open grid with a wall at x = 2 except for y = 4
+        let successors = |(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|(nx, ny)| (0..5).contains(nx) && (0..5).contains(ny) && (*nx != 2 || *ny == 4))
+                .map(|n| (n, 1))
+                .collect::<Vec<((i32, i32), u32)>>()
+        };
+        let heuristic = |(x, y): &(i32, i32)| ((4 - x).abs() + y.abs()) as u32;
+
+        let path = astar((0, 0), successors, heuristic, |n| *n == (4, 0)).unwrap();
+
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.nodes.len(), 13);
+        assert!(path.nodes.contains(&(2, 4)));
+        assert_eq!(dijkstra((0, 0), successors, |n| *n == (4, 0)).unwrap().cost, path.cost);
+    }
+
+    #[test]
+    pub fn test_astar_inconsistent_heuristic() {
+        // The cheapest path to "a" goes through "b", which the heuristic makes
+        // look expensive, so "a" is first explored at a higher cost
+        let successors = |node: &&'static str| match *node {
+            "s" => vec![("a", 4), ("b", 1)],
+            "b" => vec![("a", 1)],
+            "a" => vec![("t", 5)],
+            _ => vec![],
+        };
+        let heuristic = |node: &&'static str| if *node == "b" { 5 } else { 0 };
+
+        let path = astar("s", successors, heuristic, |n| *n == "t").unwrap();
+
+        assert_eq!(path, Path { nodes: vec!["s", "b", "a", "t"], cost: 7 });
+    }
+
+    #[test]
+    pub fn test_bfs() {
+        let successors = |n: &&'static str| edges(n).into_iter().map(|(m, _)| m);
+
+        assert_eq!(bfs("a", successors, |n| *n == "d"), Some(vec!["a", "d"]));
+        assert_eq!(bfs("c", successors, |n| *n == "b"), None);
+    }
+}
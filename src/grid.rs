@@ -0,0 +1,252 @@
+use std::ops::{Index, IndexMut};
+
+use crate::error::{ParseError, Source};
+
+const OFFSETS_4: [(i64, i64); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const OFFSETS_8: [(i64, i64); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1,  0),          (1,  0),
+    (-1,  1), (0,  1), (1,  1),
+];
+
+/// Dense, rectangular 2D map addressed by `(x, y)`, with `(0, 0)` in the top left
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from a list of rows, unless they differ in length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        let height = rows.len();
+        let cells = rows.into_iter().flatten().collect();
+        Some(Grid { width, height, cells })
+    }
+
+    /// Parses a map with one cell per character, using `f` to convert each of them
+    pub fn parse_chars<'a, F>(source: &Source<'a>, text: &'a str, expected: &'static str, f: F)
+        -> Result<Grid<T>, ParseError> where F: Fn(char) -> Option<T> {
+        let mut rows = Vec::new();
+        for line in text.lines() {
+            let row = line
+                .char_indices()
+                .map(|(pos, c)| f(c).ok_or_else(|| source.error(&line[pos..pos + c.len_utf8()], expected)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+
+            // All rows need to be as long as the first one
+            if let Some(first) = rows.first().map(|first: &Vec<T>| first.len()) {
+                if row.len() < first {
+                    return Err(source.error(&line[line.len()..], expected));
+                }
+                if row.len() > first {
+                    let (pos, _) = line.char_indices().nth(first).unwrap();
+                    return Err(source.error(&line[pos..], "end of line"));
+                }
+            }
+
+            rows.push(row);
+        }
+
+        if rows.is_empty() || rows[0].is_empty() {
+            return Err(source.error(text, expected));
+        }
+
+        Ok(Grid::from_rows(rows).expect("Rows have been checked to be of equal length"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Position of `(x, y)` in row-major order, as used by `coords_of`
+    pub fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        if self.contains(x, y) {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn coords_of(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// All cells along with their coordinates, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| (self.coords_of(i), value))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U> where F: Fn(&T) -> U {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    fn offset_neighbors<'a>(&'a self, x: usize, y: usize, offsets: &'a [(i64, i64)])
+        -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets
+            .iter()
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(move |(nx, ny)| *nx >= 0 && *ny >= 0 && self.contains(*nx as usize, *ny as usize))
+            .map(|(nx, ny)| (nx as usize, ny as usize))
+    }
+
+    /// Coordinates of the up to 4 horizontally and vertically adjacent cells
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_neighbors(x, y, &OFFSETS_4)
+    }
+
+    /// Coordinates of the up to 8 adjacent cells, including diagonals
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_neighbors(x, y, &OFFSETS_8)
+    }
+
+    /// Renders the grid as text, one line per row
+    pub fn render<F>(&self, f: F) -> String where F: Fn(&T) -> char {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&f));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a map of single decimal digits, such as height or risk levels
+    pub fn parse_digits<'a>(source: &Source<'a>, text: &'a str, expected: &'static str)
+        -> Result<Grid<u8>, ParseError> {
+        Grid::parse_chars(source, text, expected, |c| c.to_digit(10).map(|d| d as u8))
+    }
+
+    pub fn render_digits(&self) -> String {
+        self.render(|d| char::from_digit(*d as u32, 10).unwrap_or('?'))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| panic!("({}, {}) is outside of {}x{} grid", x, y, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| panic!("({}, {}) is outside of {}x{} grid", x, y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn sample() -> Grid<u8> {
+        let text = "123\n456";
+        Grid::parse_digits(&Source::new(0, text), text, "digit").unwrap()
+    }
+
+    #[test]
+    pub fn test_parse_digits() {
+        let grid = sample();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(0, 1)], 4);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.render_digits(), "123\n456\n");
+    }
+
+    #[test]
+    pub fn test_parse_digits_errors() {
+        let text = "123\n4x6";
+        let error = Grid::parse_digits(&Source::new(9, text), text, "digit").unwrap_err();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 2, "x"));
+
+        let text = "123\n45";
+        let error = Grid::parse_digits(&Source::new(9, text), text, "digit").unwrap_err();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 3, ""));
+
+        let text = "123\n4567";
+        let error = Grid::parse_digits(&Source::new(9, text), text, "digit").unwrap_err();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 4, "7"));
+    }
+
+    #[test]
+    pub fn test_neighbors() {
+        let grid = sample();
+
+        assert_eq!(HashSet::<_>::from_iter(grid.neighbors4(0, 0)), HashSet::from_iter([(1, 0), (0, 1)]));
+        assert_eq!(HashSet::<_>::from_iter(grid.neighbors4(1, 1)), HashSet::from_iter([(0, 1), (2, 1), (1, 0)]));
+        assert_eq!(HashSet::<_>::from_iter(grid.neighbors8(2, 1)), HashSet::from_iter([(1, 0), (2, 0), (1, 1)]));
+        assert_eq!(grid.neighbors8(1, 0).count(), 5);
+    }
+
+    #[test]
+    pub fn test_from_rows() {
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+
+        let grid = Grid::from_rows(vec![vec!['a', 'b'], vec!['c', 'd']]).unwrap();
+
+        assert_eq!(grid.row(1), ['c', 'd']);
+        assert_eq!(grid.coords_of(3), (1, 1));
+        assert_eq!(grid.render(|c| *c), "ab\ncd\n");
+    }
+}
@@ -0,0 +1,286 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const YEAR: u32 = 2021;
+pub const INPUTS_DIR: &str = "inputs";
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing(PathBuf),
+    Io(PathBuf, io::Error),
+    Config(PathBuf, String),
+    Fetch(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Missing(path) =>
+                write!(f, "No input at {} and no session configured in {} to fetch it",
+                    path.display(), CONFIG_FILE),
+            InputError::Io(path, e) => write!(f, "Cannot access {}: {}", path.display(), e),
+            InputError::Config(path, e) => write!(f, "Invalid config {}: {}", path.display(), e),
+            InputError::Fetch(e) => write!(f, "Cannot fetch input: {}", e),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// Source of puzzle inputs that are not cached yet
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u8) -> Result<String, InputError>;
+}
+
+/// Downloads inputs from the Advent of Code website, authenticated by a session cookie
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+}
+
+impl HttpFetcher {
+    pub const DEFAULT_BASE_URL: &'static str = "https://adventofcode.com";
+
+    pub fn new(base_url: &str, session: &str) -> HttpFetcher {
+        HttpFetcher {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+        }
+    }
+
+    /// Reads the session token from a config file such as
+    ///
+    /// ```toml
+    /// session = "53616c7465645f5f..."
+    /// # base_url = "https://adventofcode.com"
+    /// ```
+    pub fn from_config(path: &Path) -> Result<HttpFetcher, InputError> {
+        let text = fs::read_to_string(path).map_err(|e| InputError::Io(path.to_owned(), e))?;
+        let config = text
+            .parse::<toml::Table>()
+            .map_err(|e| InputError::Config(path.to_owned(), e.to_string()))?;
+
+        let get = |key: &str| match config.get(key) {
+            Some(toml::Value::String(s)) => Ok(Some(s.as_str())),
+            Some(_) => Err(InputError::Config(path.to_owned(), format!("'{}' has to be a string", key))),
+            None => Ok(None),
+        };
+
+        let session = get("session")?
+            .ok_or_else(|| InputError::Config(path.to_owned(), String::from("'session' is missing")))?;
+        let base_url = get("base_url")?.unwrap_or(HttpFetcher::DEFAULT_BASE_URL);
+
+        Ok(HttpFetcher::new(base_url, session))
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u32, day: u8) -> Result<String, InputError> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+
+        ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/dnnr/advent-of-code-2021")
+            .call()
+            .map_err(|e| InputError::Fetch(format!("{}: {}", url, e)))?
+            .into_string()
+            .map_err(|e| InputError::Fetch(format!("{}: {}", url, e)))
+    }
+}
+
+/// Strips trailing whitespace from every line and from the end of the input
+pub fn normalize(input: &str) -> String {
+    let mut out = input
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim_end()
+        .to_owned();
+    out.push('\n');
+    out
+}
+
+/// Cache of puzzle inputs in `<root>/<year>/dayNN.txt`, filled by an optional
+/// fetcher. Inputs that are cached already are never fetched again, including
+/// those of this year that are still cached in the older `<root>/dayNN.txt`.
+pub struct InputStore {
+    root: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl InputStore {
+    pub fn new<P: AsRef<Path>>(root: P) -> InputStore {
+        InputStore { root: root.as_ref().to_owned(), fetcher: None }
+    }
+
+    /// Store in `inputs/`, fetching missing inputs if `aoc.toml` exists
+    pub fn from_config() -> Result<InputStore, InputError> {
+        let store = InputStore::new(INPUTS_DIR);
+        let config = Path::new(CONFIG_FILE);
+        if config.exists() {
+            Ok(store.with_fetcher(Box::new(HttpFetcher::from_config(config)?)))
+        } else {
+            Ok(store)
+        }
+    }
+
+    pub fn with_fetcher(self, fetcher: Box<dyn Fetcher>) -> InputStore {
+        InputStore { fetcher: Some(fetcher), ..self }
+    }
+
+    pub fn path(&self, year: u32, day: u8) -> PathBuf {
+        self.root.join(year.to_string()).join(format!("day{:02}.txt", day))
+    }
+
+    // Moves an input cached before inputs were kept by year into place
+    fn migrate(&self, year: u32, day: u8, path: &Path) -> Result<(), InputError> {
+        let legacy = self.root.join(format!("day{:02}.txt", day));
+        if year != YEAR || path.exists() || !legacy.exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| fs::rename(&legacy, path))
+            .map_err(|e| InputError::Io(legacy, e))
+    }
+
+    pub fn get(&self, year: u32, day: u8) -> Result<String, InputError> {
+        let path = self.path(year, day);
+        self.migrate(year, day, &path)?;
+        match fs::read_to_string(&path) {
+            Ok(input) => return Ok(normalize(&input)),
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(InputError::Io(path, e)),
+            Err(_) => (),
+        }
+
+        let fetcher = self.fetcher.as_ref().ok_or_else(|| InputError::Missing(path.clone()))?;
+        let input = normalize(&fetcher.fetch(year, day)?);
+
+        fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| fs::write(&path, &input))
+            .map_err(|e| InputError::Io(path, e))?;
+
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    // Serves `body` with `status` for every request, counting the requests made
+    // with the expected session cookie
+    fn mock_server(status: &'static str, body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let headers = BufReader::new(&stream)
+                    .lines()
+                    .map(|l| l.unwrap())
+                    .take_while(|l| !l.is_empty())
+                    .collect::<Vec<String>>();
+
+                if headers[0] == "GET /2021/day/6/input HTTP/1.1"
+                    && headers.iter().any(|h| h.eq_ignore_ascii_case("cookie: session=secret")) {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status, body.len(), body).unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[test]
+    pub fn test_normalize() {
+        assert_eq!(normalize("1,2 \r\n3\t\n\n\n"), "1,2\n3\n");
+        assert_eq!(normalize("  #A#B#\n"), "  #A#B#\n");
+    }
+
+    #[test]
+    pub fn test_fetch_once_and_cache() {
+        let (url, requests) = mock_server("200 OK", "3,4,3,1,2 \n\n");
+        let dir = temp_dir("cache");
+        let store = InputStore::new(&dir).with_fetcher(Box::new(HttpFetcher::new(&url, "secret")));
+
+        assert_eq!(store.get(2021, 6).unwrap(), "3,4,3,1,2\n");
+        assert_eq!(store.get(2021, 6).unwrap(), "3,4,3,1,2\n");
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(fs::read_to_string(dir.join("2021").join("day06.txt")).unwrap(), "3,4,3,1,2\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_fetch_failure_not_cached() {
+        let (url, _) = mock_server("400 Bad Request", "Please log in");
+        let dir = temp_dir("failure");
+        let store = InputStore::new(&dir).with_fetcher(Box::new(HttpFetcher::new(&url, "secret")));
+
+        assert!(matches!(store.get(2021, 6), Err(InputError::Fetch(_))));
+        assert!(!store.path(2021, 6).exists());
+    }
+
+    #[test]
+    pub fn test_migrate_legacy_cache() {
+        let (url, requests) = mock_server("200 OK", "fetched");
+        let dir = temp_dir("legacy");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day06.txt"), "3,4,3,1,2\r\n").unwrap();
+        let store = InputStore::new(&dir).with_fetcher(Box::new(HttpFetcher::new(&url, "secret")));
+
+        assert_eq!(store.get(YEAR, 6).unwrap(), "3,4,3,1,2\n");
+
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+        assert!(store.path(YEAR, 6).exists());
+        assert!(!dir.join("day06.txt").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_offline_missing() {
+        let dir = temp_dir("offline");
+        let store = InputStore::new(&dir);
+
+        assert!(matches!(store.get(2021, 6), Err(InputError::Missing(_))));
+    }
+
+    #[test]
+    pub fn test_from_config() {
+        let dir = temp_dir("config");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+
+        fs::write(&path, "session = \"secret\"\nbase_url = \"http://localhost:1/\"\n").unwrap();
+        let fetcher = HttpFetcher::from_config(&path).unwrap();
+        assert_eq!((fetcher.base_url.as_str(), fetcher.session.as_str()), ("http://localhost:1", "secret"));
+
+        fs::write(&path, "base_url = \"http://localhost:1/\"\n").unwrap();
+        assert!(matches!(HttpFetcher::from_config(&path), Err(InputError::Config(_, _))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
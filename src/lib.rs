@@ -2,20 +2,39 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod inputs;
+pub mod solution;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
-// day05: weekend, I guess?
+pub mod day05;
 pub mod day06;
 pub mod day07;
 pub mod day08;
-// day09: skipped in favor of completing day08
-// day10: Python with yogan
+pub mod day09;
+pub mod day10;
 pub mod day11;
 pub mod day12;
 pub mod day13;
 pub mod day14;
 pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 
 aoc_lib!{ year = 2021 }
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+use std::time::Instant;
+
+use advent_of_code_2021::answers::{self, Answers};
+use advent_of_code_2021::bench::{self, BenchConfig, Report, Stats};
+use advent_of_code_2021::inputs::{self, InputStore};
+use advent_of_code_2021::solution::{self, Part, Puzzle};
+
+const USAGE: &str = "Usage:
+    aoc list
+    aoc run [DAY [PART]] [--input PATH]
+    aoc verify [DAY [PART]]
+    aoc record [DAY [PART]]
+    aoc bench [DAY [PART]] [--input PATH] [--warmup N] [--iterations N]
+              [--json PATH] [--baseline PATH]
+
+Without DAY, all days are handled. Input is read from inputs/2021/dayNN.txt
+unless given with --input, where a PATH of '-' reads from stdin. Inputs found
+in the older inputs/dayNN.txt are moved there. Missing inputs
+are downloaded once if aoc.toml in the current directory holds a session token:
+
+    session = \"<value of the session cookie>\"
+
+verify compares against the answers stored in answers/dayNN.toml, record
+stores the current answers there.
+
+bench times generators and solvers separately; --json writes the results as
+JSON, --baseline compares the medians with such a file from an earlier run.";
+
+enum Command {
+    List,
+    Run { day: Option<u8>, part: Option<Part>, input: Option<String> },
+    Verify { day: Option<u8>, part: Option<Part> },
+    Record { day: Option<u8>, part: Option<Part> },
+    Bench {
+        day: Option<u8>,
+        part: Option<Part>,
+        input: Option<String>,
+        config: BenchConfig,
+        json: Option<String>,
+        baseline: Option<String>,
+    },
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(String::from("No command given")),
+    };
+
+    let allowed_options: &[&str] = match command {
+        "list" if rest.is_empty() => return Ok(Command::List),
+        "run" => &["--input"],
+        "verify" | "record" => &[],
+        "bench" => &["--input", "--warmup", "--iterations", "--json", "--baseline"],
+        _ => return Err(format!("Unknown command '{}'", command)),
+    };
+
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        let option = if arg == "-i" { "--input" } else { arg.as_str() };
+        if allowed_options.contains(&option) {
+            let value = rest.next().ok_or(format!("Missing value for {}", option))?;
+            options.insert(option, value.clone());
+        } else if option.starts_with("--") {
+            return Err(format!("Unknown option '{}' for {}", option, command));
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    let mut numbers = positional
+        .iter()
+        .map(|x| x.parse::<u8>().map_err(|_| format!("Invalid number '{}'", x)));
+    let day = numbers.next().transpose()?;
+    let part = numbers
+        .next()
+        .transpose()?
+        .map(|p| Part::from_number(p).ok_or(format!("Invalid part '{}'", p)))
+        .transpose()?;
+    if numbers.next().is_some() {
+        return Err(String::from("Too many arguments"));
+    }
+
+    let input = options.remove("--input");
+    if day.is_none() && input.is_some() {
+        return Err(String::from("--input requires a DAY"));
+    }
+
+    let count = |name: &str, default: usize| -> Result<usize, String> {
+        options.get(name).map_or(Ok(default), |x| x
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid value '{}' for {}", x, name)))
+    };
+
+    Ok(match command {
+        "run" => Command::Run { day, part, input },
+        "verify" => Command::Verify { day, part },
+        "record" => Command::Record { day, part },
+        _ => {
+            let default = BenchConfig::default();
+            let config = BenchConfig {
+                warmup: count("--warmup", default.warmup)?,
+                iterations: count("--iterations", default.iterations)?,
+            };
+            Command::Bench {
+                day,
+                part,
+                input,
+                config,
+                json: options.remove("--json"),
+                baseline: options.remove("--baseline"),
+            }
+        },
+    })
+}
+
+fn read_input(day: u8, input: Option<&str>) -> Result<String, String> {
+    match input {
+        Some("-") => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("Cannot read stdin: {}", e))?;
+            Ok(inputs::normalize(&buffer))
+        },
+        Some(path) => fs::read_to_string(path)
+            .map(|input| inputs::normalize(&input))
+            .map_err(|e| format!("Cannot read {}: {}", path, e)),
+        None => InputStore::from_config()
+            .and_then(|store| store.get(inputs::YEAR, day))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn select_puzzles(day: Option<u8>) -> Result<Vec<&'static Puzzle>, String> {
+    match day {
+        Some(day) => Ok(vec![solution::find(day).map_err(|e| e.to_string())?]),
+        None => Ok(solution::puzzles().iter().collect::<Vec<&Puzzle>>()),
+    }
+}
+
+fn select_parts(puzzle: &Puzzle, part: Option<Part>) -> Vec<Part> {
+    puzzle.parts()
+        .iter()
+        .filter(|p| part.is_none_or(|q| **p == q))
+        .cloned()
+        .collect()
+}
+
+// Multi-line answers (such as letters drawn with '#') go on their own lines
+fn format_answer(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("\n{}", answer.trim_end())
+    } else {
+        format!(" {}", answer)
+    }
+}
+
+fn run_part(puzzle: &Puzzle, part: Part, input: &str) -> Result<(), String> {
+    let start_time = Instant::now();
+    let parsed = puzzle.parse(input)
+        .map_err(|e| format!("Day {} - Part {}: generator failed: {}", puzzle.day, part, e))?;
+
+    let inter_time = Instant::now();
+    let result = puzzle.solve(&parsed, part)
+        .map_err(|e| format!("Day {} - Part {}: solver failed: {}", puzzle.day, part, e))?;
+    let final_time = Instant::now();
+
+    println!("Day {} - Part {}:{}", puzzle.day, part, format_answer(&result.to_string()));
+    println!("\tgenerator: {:?},\n\tsolver: {:?}", inter_time - start_time, final_time - inter_time);
+
+    Ok(())
+}
+
+fn run(day: Option<u8>, part: Option<Part>, input: Option<&str>) -> Result<(), String> {
+    let mut failures = 0;
+    for puzzle in select_puzzles(day)? {
+        let input = match read_input(puzzle.day, input) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {}: {}", puzzle.day, e);
+                failures += 1;
+                continue;
+            },
+        };
+
+        for part in select_parts(puzzle, part) {
+            if let Err(e) = run_part(puzzle, part, &input) {
+                eprintln!("{}", e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} failure(s)", failures));
+    }
+    Ok(())
+}
+
+fn verify(day: Option<u8>, part: Option<Part>) -> Result<(), String> {
+    let (mut passed, mut failures, mut skipped) = (0, 0, 0);
+
+    for puzzle in select_puzzles(day)? {
+        let answers = match Answers::load(puzzle.day) {
+            Ok(Some(answers)) => answers,
+            Ok(None) => {
+                println!("Day {}: skipped, no answers in {}",
+                    puzzle.day, answers::answers_path(puzzle.day).display());
+                skipped += 1;
+                continue;
+            },
+            Err(e) => {
+                eprintln!("Day {}: {}", puzzle.day, e);
+                failures += 1;
+                continue;
+            },
+        };
+
+        let parsed = match read_input(puzzle.day, None)
+            .and_then(|input| puzzle.parse(&input).map_err(|e| e.to_string())) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Day {}: {}", puzzle.day, e);
+                failures += 1;
+                continue;
+            },
+        };
+
+        for part in select_parts(puzzle, part) {
+            let expected = match answers.get(part) {
+                Some(expected) => expected,
+                None => {
+                    println!("Day {} - Part {}: skipped, no stored answer", puzzle.day, part);
+                    skipped += 1;
+                    continue;
+                },
+            };
+
+            let actual = match puzzle.solve(&parsed, part) {
+                Ok(actual) => actual.to_string(),
+                Err(e) => {
+                    eprintln!("Day {} - Part {}: {}", puzzle.day, part, e);
+                    failures += 1;
+                    continue;
+                },
+            };
+            if actual == expected {
+                println!("Day {} - Part {}: ok", puzzle.day, part);
+                passed += 1;
+            } else {
+                println!("Day {} - Part {}: MISMATCH\n\texpected:{}\n\tactual:{}",
+                    puzzle.day, part, format_answer(expected), format_answer(&actual));
+                failures += 1;
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} skipped", passed, failures, skipped);
+    if failures > 0 {
+        return Err(format!("{} failure(s)", failures));
+    }
+    Ok(())
+}
+
+fn record(day: Option<u8>, part: Option<Part>) -> Result<(), String> {
+    let mut failures = 0;
+    for puzzle in select_puzzles(day)? {
+        let parsed = match read_input(puzzle.day, None)
+            .and_then(|input| puzzle.parse(&input).map_err(|e| e.to_string())) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Day {}: {}", puzzle.day, e);
+                failures += 1;
+                continue;
+            },
+        };
+
+        let result = Answers::load(puzzle.day).and_then(|answers| {
+            let mut answers = answers.unwrap_or_default();
+            for part in select_parts(puzzle, part) {
+                let answer = match puzzle.solve(&parsed, part) {
+                    Ok(answer) => answer.to_string(),
+                    Err(e) => {
+                        eprintln!("Day {} - Part {}: {}", puzzle.day, part, e);
+                        failures += 1;
+                        continue;
+                    },
+                };
+                println!("Day {} - Part {}:{}", puzzle.day, part, format_answer(&answer));
+                answers.set(part, answer);
+            }
+            answers.save(puzzle.day)
+        });
+
+        match result {
+            Ok(()) => println!("Day {}: recorded in {}",
+                puzzle.day, answers::answers_path(puzzle.day).display()),
+            Err(e) => {
+                eprintln!("Day {}: {}", puzzle.day, e);
+                failures += 1;
+            },
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} failure(s)", failures));
+    }
+    Ok(())
+}
+
+fn print_stats(label: &str, stats: &Stats, baseline: Option<&Stats>) {
+    match baseline {
+        Some(baseline) => println!("{}: {}, {:+.1}% vs. baseline",
+            label, stats, 100.0 * bench::median_change(baseline, stats)),
+        None => println!("{}: {}", label, stats),
+    }
+}
+
+fn bench(day: Option<u8>, part: Option<Part>, input: Option<&str>, config: &BenchConfig,
+    json: Option<&str>, baseline: Option<&str>) -> Result<(), String> {
+    let baseline = baseline
+        .map(|path| fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path, e))
+            .and_then(|s| Report::from_json(&s).map_err(|e| format!("Invalid report {}: {}", path, e))))
+        .transpose()?;
+
+    let mut report = Report { warmup: config.warmup, iterations: config.iterations, days: Vec::new() };
+    let mut failures = 0;
+    for puzzle in select_puzzles(day)? {
+        let day_report = read_input(puzzle.day, input).and_then(|input|
+            bench::bench_puzzle(puzzle, &input, &select_parts(puzzle, part), config)
+                .map_err(|e| e.to_string()));
+
+        let day_report = match day_report {
+            Ok(day_report) => day_report,
+            Err(e) => {
+                eprintln!("Day {}: {}", puzzle.day, e);
+                failures += 1;
+                continue;
+            },
+        };
+
+        let day_baseline = baseline.as_ref().and_then(|b| b.day(puzzle.day));
+        print_stats(&format!("Day {} - generator", puzzle.day),
+            &day_report.generator, day_baseline.map(|b| &b.generator));
+        for part_report in &day_report.parts {
+            print_stats(&format!("Day {} - Part {}", puzzle.day, part_report.part),
+                &part_report.solver,
+                day_baseline.and_then(|b| b.part(part_report.part)).map(|b| &b.solver));
+        }
+
+        report.days.push(day_report);
+    }
+
+    if let Some(path) = json {
+        fs::write(path, report.to_json()).map_err(|e| format!("Cannot write {}: {}", path, e))?;
+        println!("Report written to {}", path);
+    }
+
+    if failures > 0 {
+        return Err(format!("{} failure(s)", failures));
+    }
+    Ok(())
+}
+
+fn list() {
+    for day in solution::FIRST_DAY..=solution::LAST_DAY {
+        match solution::find(day) {
+            Ok(puzzle) => {
+                let parts = puzzle.parts()
+                    .iter()
+                    .map(|p| format!("part{}", p))
+                    .collect::<Vec<String>>();
+                println!("day{:02}: {}", day, parts.join(", "));
+            },
+            Err(_) => println!("day{:02}: not implemented", day),
+        }
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match parse_args(&args) {
+        Ok(Command::List) => {
+            list();
+            Ok(())
+        },
+        Ok(Command::Run { day, part, input }) => run(day, part, input.as_deref()),
+        Ok(Command::Verify { day, part }) => verify(day, part),
+        Ok(Command::Record { day, part }) => record(day, part),
+        Ok(Command::Bench { day, part, input, config, json, baseline }) =>
+            bench(day, part, input.as_deref(), &config, json.as_deref(), baseline.as_deref()),
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        },
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,225 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::error::{ParseError, SolveError};
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09};
+use crate::{day10, day11, day12, day13, day14, day15, day16, day17, day18, day19};
+use crate::{day20, day21, day22, day23, day24, day25};
+
+pub const FIRST_DAY: u8 = 1;
+pub const LAST_DAY: u8 = 25;
+
+/// A day's puzzle: an input parser plus the solvers for its parts
+pub trait Solution {
+    const DAY: u8;
+    /// Parts the puzzle has, which is only the first one on the last day
+    const PARTS: &'static [Part] = &Part::ALL;
+    type Input: 'static;
+    type Output: Display + 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Result<Self::Output, SolveError>;
+
+    fn part2(_input: &Self::Input) -> Result<Self::Output, SolveError> {
+        Err(SolveError::new(Self::DAY, "there is no part 2"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// Answer to a part of a registered puzzle, as returned by its solver
+pub type Answer = Box<dyn Display>;
+
+/// Parsed input of a registered puzzle, only to be passed back to the same puzzle
+pub struct ParsedInput(Box<dyn Any>);
+
+/// Type-erased handle on a `Solution`, as kept in the registry
+pub struct Puzzle {
+    pub day: u8,
+    parts: &'static [Part],
+    parse: fn(&str) -> Result<ParsedInput, ParseError>,
+    solve: fn(&ParsedInput, Part) -> Result<Answer, SolveError>,
+}
+
+fn parse_erased<S: Solution>(input: &str) -> Result<ParsedInput, ParseError> {
+    Ok(ParsedInput(Box::new(S::parse(input)?)))
+}
+
+fn solve_erased<S: Solution>(input: &ParsedInput, part: Part) -> Result<Answer, SolveError> {
+    let input = input.0
+        .downcast_ref::<S::Input>()
+        .unwrap_or_else(|| panic!("Input passed to day {} was parsed by another day", S::DAY));
+
+    Ok(match part {
+        Part::One => Box::new(S::part1(input)?),
+        Part::Two => Box::new(S::part2(input)?),
+    })
+}
+
+impl Puzzle {
+    pub const fn of<S: Solution>() -> Puzzle {
+        Puzzle { day: S::DAY, parts: S::PARTS, parse: parse_erased::<S>, solve: solve_erased::<S> }
+    }
+
+    pub fn parts(&self) -> &'static [Part] {
+        self.parts
+    }
+
+    /// Parses the raw puzzle input. Trailing newlines are dropped beforehand, the
+    /// same way cargo-aoc does before calling an input generator.
+    pub fn parse(&self, input: &str) -> Result<ParsedInput, ParseError> {
+        (self.parse)(input.trim_end_matches('\n'))
+    }
+
+    /// Solves one part, failing for parts the puzzle does not have and for
+    /// inputs that have no answer
+    pub fn solve(&self, input: &ParsedInput, part: Part) -> Result<Answer, SolveError> {
+        if !self.parts.contains(&part) {
+            return Err(SolveError::new(self.day, format!("there is no part {}", part)));
+        }
+
+        (self.solve)(input, part)
+    }
+}
+
+static PUZZLES: &[Puzzle] = &[
+    Puzzle::of::<day01::Day01>(),
+    Puzzle::of::<day02::Day02>(),
+    Puzzle::of::<day03::Day03>(),
+    Puzzle::of::<day04::Day04>(),
+    Puzzle::of::<day05::Day05>(),
+    Puzzle::of::<day06::Day06>(),
+    Puzzle::of::<day07::Day07>(),
+    Puzzle::of::<day08::Day08>(),
+    Puzzle::of::<day09::Day09>(),
+    Puzzle::of::<day10::Day10>(),
+    Puzzle::of::<day11::Day11>(),
+    Puzzle::of::<day12::Day12>(),
+    Puzzle::of::<day13::Day13>(),
+    Puzzle::of::<day14::Day14>(),
+    Puzzle::of::<day15::Day15>(),
+    Puzzle::of::<day16::Day16>(),
+    Puzzle::of::<day17::Day17>(),
+    Puzzle::of::<day18::Day18>(),
+    Puzzle::of::<day19::Day19>(),
+    Puzzle::of::<day20::Day20>(),
+    Puzzle::of::<day21::Day21>(),
+    Puzzle::of::<day22::Day22>(),
+    Puzzle::of::<day23::Day23>(),
+    Puzzle::of::<day24::Day24>(),
+    Puzzle::of::<day25::Day25>(),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupError {
+    NoSuchDay(u8),
+    Unimplemented(u8),
+}
+
+impl Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LookupError::NoSuchDay(day) =>
+                write!(f, "There is no day {}, days go from {} to {}", day, FIRST_DAY, LAST_DAY),
+            LookupError::Unimplemented(day) => write!(f, "Day {} is not implemented", day),
+        }
+    }
+}
+
+impl Error for LookupError {}
+
+/// All implemented puzzles, ordered by day
+pub fn puzzles() -> &'static [Puzzle] {
+    PUZZLES
+}
+
+pub fn find(day: u8) -> Result<&'static Puzzle, LookupError> {
+    if !(FIRST_DAY..=LAST_DAY).contains(&day) {
+        return Err(LookupError::NoSuchDay(day));
+    }
+
+    PUZZLES
+        .iter()
+        .find(|p| p.day == day)
+        .ok_or(LookupError::Unimplemented(day))
+}
+
+pub fn unimplemented_days() -> Vec<u8> {
+    (FIRST_DAY..=LAST_DAY)
+        .filter(|day| find(*day).is_err())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_puzzles_ordered() {
+        let days = puzzles().iter().map(|p| p.day).collect::<Vec<u8>>();
+
+        assert!(days.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    pub fn test_unimplemented_days() {
+        assert_eq!(unimplemented_days(), Vec::<u8>::new());
+        assert_eq!(find(26).err(), Some(LookupError::NoSuchDay(26)));
+    }
+
+    #[test]
+    pub fn test_parts() {
+        assert_eq!(find(24).unwrap().parts(), Part::ALL);
+        assert_eq!(find(25).unwrap().parts(), [Part::One]);
+    }
+
+    #[test]
+    pub fn test_solve_registered() {
+        let puzzle = find(6).unwrap();
+
+        let input = puzzle.parse("3,4,3,1,2\n").unwrap();
+
+        assert_eq!(puzzle.solve(&input, Part::One).unwrap().to_string(), "5934");
+        assert_eq!(puzzle.solve(&input, Part::Two).unwrap().to_string(), "26984457539");
+    }
+
+    #[test]
+    pub fn test_solve_missing_part() {
+        let puzzle = find(25).unwrap();
+
+        let input = puzzle.parse(">>\n").unwrap();
+
+        assert_eq!(puzzle.solve(&input, Part::Two).err(), Some(SolveError::new(25, "there is no part 2")));
+        assert!(puzzle.solve(&input, Part::One).is_ok());
+    }
+}
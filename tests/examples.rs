@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::PathBuf;
+
+use advent_of_code_2021::answers::Answers;
+use advent_of_code_2021::solution;
+
+/// Solves examples/dayNN/NAME.txt and compares against examples/dayNN/NAME.expected,
+/// skipping parts that have no expected answer
+fn check_example(day: u8, name: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join(format!("day{:02}", day));
+    let read = |extension: &str| {
+        let path = dir.join(name).with_extension(extension);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Cannot read {}: {}", path.display(), e))
+    };
+
+    let puzzle = solution::find(day).unwrap_or_else(|e| panic!("{}", e));
+    let expected = Answers::from_toml(&read("expected"))
+        .unwrap_or_else(|e| panic!("Invalid {}.expected: {}", name, e));
+
+    let input = puzzle.parse(&read("txt")).unwrap_or_else(|e| panic!("{}", e));
+
+    let mut checked = 0;
+    for &part in puzzle.parts() {
+        if let Some(answer) = expected.get(part) {
+            let actual = puzzle.solve(&input, part).unwrap_or_else(|e| panic!("{}", e));
+            assert_eq!(actual.to_string(), answer, "day {} part {}", day, part);
+            checked += 1;
+        }
+    }
+    assert!(checked > 0, "{}.expected has no answers", name);
+}
+
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
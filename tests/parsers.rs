@@ -0,0 +1,25 @@
+use proptest::prelude::*;
+
+use advent_of_code_2021::solution;
+
+// Characters that make up the puzzle inputs, to get past the first token more
+// often than arbitrary bytes do
+const INPUT_ALPHABET: &str = "[0-9a-zA-Z ,|=>#.\n-]{0,256}";
+
+fn parse_all(input: &str) {
+    for puzzle in solution::puzzles() {
+        let _ = puzzle.parse(input);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_generators_never_panic_on_bytes(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        parse_all(&String::from_utf8_lossy(&bytes));
+    }
+
+    #[test]
+    fn test_generators_never_panic_on_text(input in INPUT_ALPHABET) {
+        parse_all(&input);
+    }
+}